@@ -0,0 +1,218 @@
+//! Generates keypairs and signed test requests for exercising the handler locally.
+//!
+//! ```text
+//! sign keygen
+//! sign request --key <signing key hex> --body <file> [--timestamp <secs>] [--format headers|curl|bruno] [--url <url>]
+//! ```
+use ed25519_dalek::SigningKey;
+use hex::{FromHex, ToHex};
+use rand::rngs::OsRng;
+use serverless_discord::security::{generate_signature, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use std::env;
+use std::process::ExitCode;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "Usage:
+  sign keygen
+  sign request --key <signing key hex> --body <file> [--timestamp <secs>] [--format headers|curl|bruno] [--url <url>]";
+
+const DEFAULT_URL: &str = "http://localhost:9000/";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Headers,
+    Curl,
+    Bruno,
+}
+
+struct SignedRequest {
+    url: String,
+    signature: String,
+    timestamp: String,
+    body: String,
+}
+
+impl SignedRequest {
+    /// Signs `body` without its trailing newline, which most editors add but the Bruno format
+    /// cannot reproduce, so every format sends exactly the signed bytes.
+    fn new(signing_key: &SigningKey, url: String, timestamp: String, body: &str) -> Self {
+        let body = body
+            .strip_suffix("\r\n")
+            .or_else(|| body.strip_suffix('\n'))
+            .unwrap_or(body);
+        let signature = generate_signature(signing_key, body.as_bytes(), timestamp.as_bytes());
+        SignedRequest {
+            url,
+            signature: signature.to_bytes().encode_hex(),
+            timestamp,
+            body: body.to_string(),
+        }
+    }
+
+    fn render(&self, format: Format) -> String {
+        match format {
+            Format::Headers => format!(
+                "{}: {}\n{}: {}",
+                SIGNATURE_HEADER, self.signature, TIMESTAMP_HEADER, self.timestamp
+            ),
+            Format::Curl => format!(
+                "curl -X POST '{}' \\\n  -H 'content-type: application/json' \\\n  -H '{}: {}' \\\n  -H '{}: {}' \\\n  --data-binary '{}'",
+                self.url,
+                SIGNATURE_HEADER,
+                self.signature,
+                TIMESTAMP_HEADER,
+                self.timestamp,
+                self.body.replace('\'', r"'\''")
+            ),
+            Format::Bruno => format!(
+                "meta {{\n  name: signed request\n  type: http\n  seq: 1\n}}\n\npost {{\n  url: {}\n  body: json\n  auth: inherit\n}}\n\nheaders {{\n  {}: {}\n  {}: {}\n}}\n\nbody:json {{\n{}\n}}\n",
+                self.url,
+                SIGNATURE_HEADER,
+                self.signature,
+                TIMESTAMP_HEADER,
+                self.timestamp,
+                self.body
+                    .lines()
+                    .map(|line| format!("  {}", line))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        }
+    }
+}
+
+fn keygen() {
+    let signing_key = SigningKey::generate(&mut OsRng);
+    println!("Signing key: {}", signing_key.to_bytes().encode_hex::<String>());
+    println!("Public key:  {}", signing_key.verifying_key().to_bytes().encode_hex::<String>());
+}
+
+fn request(mut args: impl Iterator<Item = String>) -> Result<String, String> {
+    let mut key = None;
+    let mut body_path = None;
+    let mut timestamp = None;
+    let mut format = Format::Headers;
+    let mut url = DEFAULT_URL.to_string();
+
+    while let Some(flag) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {}", flag))?;
+        match flag.as_str() {
+            "--key" => key = Some(value),
+            "--body" => body_path = Some(value),
+            "--timestamp" => timestamp = Some(value),
+            "--url" => url = value,
+            "--format" => {
+                format = match value.as_str() {
+                    "headers" => Format::Headers,
+                    "curl" => Format::Curl,
+                    "bruno" => Format::Bruno,
+                    other => return Err(format!("Unknown format {}", other)),
+                }
+            }
+            other => return Err(format!("Unknown option {}", other)),
+        }
+    }
+
+    let key = key.ok_or("Missing --key")?;
+    let signing_key = <[u8; 32]>::from_hex(key.trim())
+        .map(|bytes| SigningKey::from_bytes(&bytes))
+        .map_err(|_| "The signing key must be 64 hex characters".to_string())?;
+
+    let body_path = body_path.ok_or("Missing --body")?;
+    let body = std::fs::read_to_string(&body_path)
+        .map_err(|e| format!("Failed to read {}: {}", body_path, e))?;
+
+    let timestamp = match timestamp {
+        Some(timestamp) => timestamp,
+        None => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_err(|e| e.to_string())?
+            .as_secs()
+            .to_string(),
+    };
+
+    let request = SignedRequest::new(&signing_key, url, timestamp, &body);
+    if format == Format::Bruno && (request.body.contains('\r') || request.body.ends_with('\n')) {
+        return Err(
+            "The Bruno format cannot reproduce carriage returns or blank trailing lines in the body"
+                .to_string(),
+        );
+    }
+    Ok(request.render(format))
+}
+
+fn main() -> ExitCode {
+    let mut args = env::args().skip(1);
+    let result = match args.next().as_deref() {
+        Some("keygen") => {
+            keygen();
+            Ok(())
+        }
+        Some("request") => request(args).map(|output| println!("{}", output)),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Format, SignedRequest};
+    use ed25519_dalek::SigningKey;
+    use serverless_discord::security::verify_discord_request;
+
+    #[test]
+    fn render_signed_request() {
+        let request = SignedRequest {
+            url: "http://localhost:9000/".to_string(),
+            signature: "abcd".to_string(),
+            timestamp: "1746297636".to_string(),
+            body: "{\"type\":1,\"note\":\"it's\"}".to_string(),
+        };
+
+        assert_eq!(
+            request.render(Format::Headers),
+            "x-signature-ed25519: abcd\nx-signature-timestamp: 1746297636"
+        );
+        assert!(request
+            .render(Format::Curl)
+            .ends_with(r#"--data-binary '{"type":1,"note":"it'\''s"}'"#));
+        assert!(request
+            .render(Format::Bruno)
+            .contains("headers {\n  x-signature-ed25519: abcd\n  x-signature-timestamp: 1746297636\n}"));
+
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let request = SignedRequest::new(
+            &signing_key,
+            "http://localhost:9000/".to_string(),
+            "1746297636".to_string(),
+            "{\n  \"type\": 1\n}\n",
+        );
+        let rendered = request.render(Format::Bruno);
+        assert!(rendered.ends_with("body:json {\n  {\n    \"type\": 1\n  }\n}\n"));
+
+        let (_, block) = rendered.split_once("body:json {\n").unwrap();
+        let sent = block
+            .strip_suffix("\n}\n")
+            .unwrap()
+            .lines()
+            .map(|line| line.strip_prefix("  ").unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(verify_discord_request(
+            &signing_key.verifying_key(),
+            request.signature.as_bytes(),
+            request.timestamp.as_bytes(),
+            sent.as_bytes()
+        )
+        .is_ok());
+    }
+}
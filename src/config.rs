@@ -0,0 +1,162 @@
+use crate::security::{self, ReplayProtection, VerificationError};
+use ed25519_dalek::VerifyingKey;
+use std::env;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+/// The environment variable holding the application's hex encoded public key.
+pub const PUBLIC_KEY_VAR: &str = "DISCORD_PUBLIC_KEY";
+/// The environment variable holding the key being rotated out, accepted alongside the current one.
+pub const PREVIOUS_PUBLIC_KEY_VAR: &str = "DISCORD_PREVIOUS_PUBLIC_KEY";
+/// The environment variable holding the application's bot token.
+pub const BOT_TOKEN_VAR: &str = "DISCORD_BOT_TOKEN";
+/// The environment variable holding the maximum age of a request timestamp in seconds, or
+/// `off` to disable replay protection, e.g. to replay recorded requests locally.
+pub const MAX_TIMESTAMP_AGE_VAR: &str = "DISCORD_MAX_TIMESTAMP_AGE";
+
+/// The maximum age of a request timestamp when [`MAX_TIMESTAMP_AGE_VAR`] is not set.
+pub const DEFAULT_MAX_TIMESTAMP_AGE: Duration = Duration::from_secs(300);
+
+/// Startup configuration that is missing or invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    MissingVariable(String),
+    InvalidPublicKey {
+        variable: String,
+        error: VerificationError,
+    },
+    InvalidDuration {
+        variable: String,
+        value: String,
+    },
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::MissingVariable(variable) => {
+                write!(f, "Environment variable {} is not set", variable)
+            }
+            ConfigError::InvalidPublicKey { variable, error } => write!(
+                f,
+                "Environment variable {} does not hold a valid public key: {}",
+                variable, error
+            ),
+            ConfigError::InvalidDuration { variable, value } => write!(
+                f,
+                "Environment variable {} must be a number of seconds or off, got {}",
+                variable, value
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Reads and parses the public key from [`PUBLIC_KEY_VAR`].
+pub fn public_key_from_env() -> Result<VerifyingKey, ConfigError> {
+    public_key_from_var(PUBLIC_KEY_VAR)
+}
+
+/// Reads and parses a public key from `variable`, e.g. `DISCORD_PUBLIC_KEY_MODERATION` when
+/// hosting several applications.
+pub fn public_key_from_var(variable: &str) -> Result<VerifyingKey, ConfigError> {
+    load_public_key(variable, env::var(variable).ok().as_deref())
+}
+
+/// Reads and parses the key from [`PREVIOUS_PUBLIC_KEY_VAR`], if set.
+pub fn previous_public_key_from_env() -> Result<Option<VerifyingKey>, ConfigError> {
+    match env::var(PREVIOUS_PUBLIC_KEY_VAR) {
+        Ok(value) if !value.trim().is_empty() => {
+            load_public_key(PREVIOUS_PUBLIC_KEY_VAR, Some(&value)).map(Some)
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Reads the bot token from [`BOT_TOKEN_VAR`], if set.
+pub fn bot_token_from_env() -> Option<String> {
+    env::var(BOT_TOKEN_VAR).ok().filter(|token| !token.trim().is_empty())
+}
+
+/// Builds the replay protection configured by [`MAX_TIMESTAMP_AGE_VAR`], or `None` if it is
+/// turned off.
+pub fn replay_protection_from_env() -> Result<Option<ReplayProtection>, ConfigError> {
+    load_replay_protection(env::var(MAX_TIMESTAMP_AGE_VAR).ok().as_deref())
+}
+
+/// Parses a maximum timestamp age read from [`MAX_TIMESTAMP_AGE_VAR`], falling back to
+/// [`DEFAULT_MAX_TIMESTAMP_AGE`] when unset or empty.
+pub fn load_replay_protection(value: Option<&str>) -> Result<Option<ReplayProtection>, ConfigError> {
+    let max_age = match value.map(str::trim).filter(|value| !value.is_empty()) {
+        None => DEFAULT_MAX_TIMESTAMP_AGE,
+        Some("off") => return Ok(None),
+        Some(value) => value
+            .parse()
+            .map(Duration::from_secs)
+            .map_err(|_| ConfigError::InvalidDuration {
+                variable: MAX_TIMESTAMP_AGE_VAR.to_string(),
+                value: value.to_string(),
+            })?,
+    };
+    Ok(Some(ReplayProtection::new(max_age)))
+}
+
+/// Parses a public key read from `variable`, treating an empty value as missing.
+pub fn load_public_key(variable: &str, value: Option<&str>) -> Result<VerifyingKey, ConfigError> {
+    let value = value
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| ConfigError::MissingVariable(variable.to_string()))?;
+
+    security::parse_public_key(value).map_err(|error| ConfigError::InvalidPublicKey {
+        variable: variable.to_string(),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        load_public_key, load_replay_protection, ConfigError, MAX_TIMESTAMP_AGE_VAR, PUBLIC_KEY_VAR,
+    };
+    use crate::security::{HexField, VerificationError};
+
+    #[test]
+    fn load_valid_public_key() {
+        let key = "f918b1bcd0ada9f101fb43d7c8412e0e9208f4f919a7f0a7e111f063513c3391";
+        assert!(load_public_key(PUBLIC_KEY_VAR, Some(key)).is_ok());
+    }
+
+    #[test]
+    fn reject_missing_or_invalid_public_key() {
+        assert_eq!(
+            load_public_key(PUBLIC_KEY_VAR, None),
+            Err(ConfigError::MissingVariable(PUBLIC_KEY_VAR.to_string()))
+        );
+        assert_eq!(
+            load_public_key(PUBLIC_KEY_VAR, Some("")),
+            Err(ConfigError::MissingVariable(PUBLIC_KEY_VAR.to_string()))
+        );
+        assert_eq!(
+            load_public_key(PUBLIC_KEY_VAR, Some("not-hex")),
+            Err(ConfigError::InvalidPublicKey {
+                variable: PUBLIC_KEY_VAR.to_string(),
+                error: VerificationError::MalformedHex(HexField::PublicKey),
+            })
+        );
+    }
+
+    #[test]
+    fn configure_replay_protection() {
+        assert!(load_replay_protection(None).unwrap().is_some());
+        assert!(load_replay_protection(Some("60")).unwrap().is_some());
+        assert!(load_replay_protection(Some("off")).unwrap().is_none());
+        assert_eq!(
+            load_replay_protection(Some("5m")).err(),
+            Some(ConfigError::InvalidDuration {
+                variable: MAX_TIMESTAMP_AGE_VAR.to_string(),
+                value: "5m".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,260 @@
+pub mod config;
+pub mod model;
+pub mod rest;
+pub mod router;
+pub mod routes;
+pub mod security;
+
+use crate::rest::RestClient;
+use crate::router::autocomplete::AutocompleteRouter;
+use crate::router::command::CommandRouter;
+use crate::router::component::ComponentRouter;
+use crate::router::event::EventRouter;
+use crate::router::modal::ModalRouter;
+use crate::security::{LabelledKey, ReplayProtection};
+use crate::config::ConfigError;
+use crate::model::primitives::Snowflake;
+use ed25519_dalek::VerifyingKey;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Serialize)]
+pub struct ApiError {
+    error: String,
+}
+
+impl ApiError {
+    fn new(error: &str) -> Self {
+        ApiError {
+            error: error.to_string(),
+        }
+    }
+}
+
+/// The name under which single-application deployments register their application.
+pub const DEFAULT_APPLICATION: &str = "default";
+
+/// A deferral deadline leaving time for the deferred response to reach Discord within its
+/// 3 second window.
+pub const DEFAULT_DEFERRAL_DEADLINE: Duration = Duration::from_millis(2500);
+
+/// The default limit on interaction request bodies, in bytes.
+pub const DEFAULT_MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// The label of the key an application is created with.
+pub const CURRENT_KEY_LABEL: &str = "current";
+/// The label of the key read from `DISCORD_PREVIOUS_PUBLIC_KEY` while rotating keys.
+pub const PREVIOUS_KEY_LABEL: &str = "previous";
+
+/// A Discord application served by this deployment, with its own keys and routers.
+pub struct Application {
+    public_keys: Vec<LabelledKey>,
+    application_id: Option<Snowflake>,
+    rest: RestClient,
+    commands: CommandRouter,
+    autocomplete: AutocompleteRouter,
+    components: ComponentRouter,
+    modals: ModalRouter,
+    events: EventRouter,
+}
+
+impl Application {
+    /// Creates an application verified with `public_key`, labelled [`CURRENT_KEY_LABEL`].
+    pub fn new(public_key: VerifyingKey) -> Self {
+        Application {
+            public_keys: vec![LabelledKey::new(CURRENT_KEY_LABEL, public_key)],
+            application_id: None,
+            rest: RestClient::unauthenticated(),
+            commands: CommandRouter::new(),
+            autocomplete: AutocompleteRouter::new(),
+            components: ComponentRouter::new(),
+            modals: ModalRouter::new(),
+            events: EventRouter::new(),
+        }
+    }
+
+    /// Creates an application from the `DISCORD_PUBLIC_KEY` and, if set,
+    /// `DISCORD_PREVIOUS_PUBLIC_KEY` and `DISCORD_BOT_TOKEN` environment variables.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a public key is unset or invalid, so the process can fail at
+    /// startup instead of rejecting every request.
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let mut application = Application::new(config::public_key_from_env()?);
+        if let Some(previous) = config::previous_public_key_from_env()? {
+            application = application.with_public_key(PREVIOUS_KEY_LABEL, previous);
+        }
+        Ok(match config::bot_token_from_env() {
+            Some(token) => application.with_bot_token(&token),
+            None => application,
+        })
+    }
+
+    /// Also accepts requests signed with `public_key`, e.g. the old key during a rotation.
+    ///
+    /// Keys are tried in the order they were added.
+    pub fn with_public_key(mut self, label: &str, public_key: VerifyingKey) -> Self {
+        self.public_keys.push(LabelledKey::new(label, public_key));
+        self
+    }
+
+    /// Only accepts interactions sent for this application ID.
+    ///
+    /// Required to tell apart applications sharing a public key at the root route.
+    pub fn with_application_id(mut self, application_id: Snowflake) -> Self {
+        self.application_id = Some(application_id);
+        self
+    }
+
+    /// Authenticates the application's REST client with `bot_token`, keeping its other
+    /// settings.
+    pub fn with_bot_token(mut self, bot_token: &str) -> Self {
+        self.rest = self.rest.with_bot_token(bot_token);
+        self
+    }
+
+    /// Replaces the REST client handlers use for interaction webhooks, e.g. to point it at
+    /// a mock server. The new client's bot token, if any, replaces the current one, otherwise
+    /// the current one is kept.
+    pub fn with_rest_client(mut self, rest: RestClient) -> Self {
+        self.rest = match (rest.bot_token(), self.rest.bot_token()) {
+            (None, Some(bot_token)) => rest.with_bot_token(bot_token),
+            _ => rest,
+        };
+        self
+    }
+
+    pub fn with_commands(mut self, commands: CommandRouter) -> Self {
+        self.commands = commands;
+        self
+    }
+
+    pub fn with_autocomplete(mut self, autocomplete: AutocompleteRouter) -> Self {
+        self.autocomplete = autocomplete;
+        self
+    }
+
+    pub fn with_components(mut self, components: ComponentRouter) -> Self {
+        self.components = components;
+        self
+    }
+
+    pub fn with_modals(mut self, modals: ModalRouter) -> Self {
+        self.modals = modals;
+        self
+    }
+
+    /// Sets the handlers for webhook events, received on the events route.
+    pub fn with_events(mut self, events: EventRouter) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn public_keys(&self) -> &[LabelledKey] {
+        &self.public_keys
+    }
+
+    pub fn application_id(&self) -> Option<&Snowflake> {
+        self.application_id.as_ref()
+    }
+
+    pub fn bot_token(&self) -> Option<&str> {
+        self.rest.bot_token()
+    }
+
+    pub fn rest(&self) -> &RestClient {
+        &self.rest
+    }
+}
+
+/// Shared state of the deployment, hosting one or more named applications.
+///
+/// Interactions posted to `/apps/{app}` are verified against the named application only.
+/// Interactions posted to `/` are verified against every application, and then handed to
+/// the verified application matching their `application_id`. Webhook events are routed the
+/// same way on `/events` and `/apps/{app}/events`.
+#[derive(Clone)]
+pub struct App {
+    applications: Arc<HashMap<String, Arc<Application>>>,
+    replay_protection: Option<Arc<ReplayProtection>>,
+    max_body_size: usize,
+    deferral_deadline: Option<Duration>,
+}
+
+impl Default for App {
+    fn default() -> Self {
+        App {
+            applications: Arc::new(HashMap::new()),
+            replay_protection: None,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            deferral_deadline: None,
+        }
+    }
+}
+
+impl App {
+    pub fn new() -> Self {
+        App::default()
+    }
+
+    /// Hosts `application` under `name`, replacing any application registered with that name.
+    pub fn with_application(mut self, name: &str, application: Application) -> Self {
+        Arc::make_mut(&mut self.applications).insert(name.to_string(), Arc::new(application));
+        self
+    }
+
+    /// Rejects requests with stale timestamps, and replayed interaction IDs if enabled.
+    pub fn with_replay_protection(mut self, replay_protection: ReplayProtection) -> Self {
+        self.replay_protection = Some(Arc::new(replay_protection));
+        self
+    }
+
+    /// Rejects request bodies larger than `max_body_size` bytes with a 413, before buffering
+    /// them for verification. Defaults to [`DEFAULT_MAX_BODY_SIZE`].
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    /// Defers interactions whose handler has not returned within `deadline`, such as
+    /// [`DEFAULT_DEFERRAL_DEADLINE`], so slow handlers never miss Discord's 3 second window.
+    ///
+    /// Commands and modal submissions are deferred with a loading state, which the handler's
+    /// eventual message replaces. An ephemeral message deletes the public loading state and is
+    /// sent as an ephemeral follow-up instead. Components are deferred with a deferred update,
+    /// so `UpdateMessage` edits the component's message and new messages are sent as
+    /// follow-ups.
+    ///
+    /// The deferral is sent through the interaction callback endpoint, and the request only
+    /// returns once the handler's message has been delivered, so nothing is left running when
+    /// a Lambda invocation is frozen. The function timeout must allow for the slowest handler.
+    pub fn with_deferral_watchdog(mut self, deadline: Duration) -> Self {
+        self.deferral_deadline = Some(deadline);
+        self
+    }
+
+    pub fn application(&self, name: &str) -> Option<&Application> {
+        self.applications.get(name).map(Arc::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Application;
+    use crate::rest::RestClient;
+    use ed25519_dalek::SigningKey;
+
+    #[test]
+    fn keep_bot_token_when_replacing_rest_client() {
+        let application = Application::new(SigningKey::from_bytes(&[7; 32]).verifying_key())
+            .with_bot_token("token")
+            .with_rest_client(RestClient::unauthenticated().with_base_url("http://127.0.0.1:9"));
+        assert_eq!(application.bot_token(), Some("token"));
+
+        let application = application.with_rest_client(RestClient::new("other"));
+        assert_eq!(application.bot_token(), Some("other"));
+    }
+}
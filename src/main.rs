@@ -1,56 +1,48 @@
-mod routes;
-mod security;
-mod model;
-
 use axum::routing::post;
 use axum::Router;
 use lambda_http::{run, tracing, Error};
-use serde::{Deserialize, Serialize};
+use serverless_discord::model::response::InteractionResponse;
+use serverless_discord::router::command::{CommandContext, CommandRouter};
+use serverless_discord::{config, routes, App, Application, DEFAULT_APPLICATION};
 
-#[derive(Serialize)]
-struct ApiError {
-    error: String,
+async fn ping(_: CommandContext) -> InteractionResponse {
+    InteractionResponse::message("Pong!")
 }
 
-#[derive(Serialize, Deserialize)]
-struct Pong {
-    #[serde(rename = "type")]
-    data: u32,
-}
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    tracing::init_default_subscriber();
 
-impl ApiError {
-    fn new(error: &str) -> Self {
-        ApiError {
-            error: error.to_string(),
-        }
-    }
-}
+    env_logger::init();
 
-#[derive(Clone)]
-struct App {
-    public_key: String,
-}
+    let commands = CommandRouter::new().chat_input("ping", ping);
+
+    let application = Application::from_env()
+        .inspect_err(|e| tracing::error!("Invalid configuration: {}", e))?
+        .with_commands(commands);
+
+    let replay_protection = config::replay_protection_from_env()
+        .inspect_err(|e| tracing::error!("Invalid configuration: {}", e))?;
 
-impl App {
-    fn new(public_key: &str) -> Self {
-        App {
-            public_key: public_key.to_string(),
+    let mut state = App::new().with_application(DEFAULT_APPLICATION, application);
+    match replay_protection {
+        Some(protection) => {
+            state = state.with_replay_protection(protection.with_interaction_dedupe());
         }
+        None => tracing::warn!("Replay protection is turned off"),
     }
-}
 
-#[tokio::main]
-async fn main() -> Result<(), Error> {
-    tracing::init_default_subscriber();
-
-    env_logger::init();
-    
     let app = Router::new()
-        .route("/", post(routes::ping_handler))
+        .route("/", post(routes::interaction_handler))
+        .route("/apps/{app}", post(routes::interaction_handler))
+        .route("/events", post(routes::event_handler))
+        .route("/apps/{app}/events", post(routes::event_handler))
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            routes::verify_signature,
+        ))
         .route_layer(axum::middleware::from_fn(routes::mw_sample))
-        .with_state(App::new(
-            "",
-        ));
+        .with_state(state);
 
     run(app).await
 }
@@ -0,0 +1,242 @@
+use crate::model::primitives::Snowflake;
+use crate::model::resolved::Resolved;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// The `data` payload of an `APPLICATION_COMMAND` interaction.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApplicationCommandData {
+    pub id: Snowflake,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub command_type: ApplicationCommandType,
+    #[serde(default)]
+    pub options: Vec<CommandDataOption>,
+    pub resolved: Option<Resolved>,
+    pub guild_id: Option<Snowflake>,
+    /// The user or message targeted by a user or message context menu command.
+    pub target_id: Option<Snowflake>,
+}
+
+/// An option supplied with a command invocation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandDataOption {
+    pub name: String,
+    pub value: CommandOptionValue,
+}
+
+impl CommandDataOption {
+    pub fn option_type(&self) -> CommandOptionType {
+        self.value.kind()
+    }
+
+    /// Whether this is the option the user is currently typing in an autocomplete interaction.
+    pub fn is_focused(&self) -> bool {
+        matches!(self.value, CommandOptionValue::Focused(_, _))
+    }
+}
+
+/// The value of a command option, typed according to the option's `type`.
+///
+/// Subcommands and subcommand groups carry their nested options instead of a value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandOptionValue {
+    SubCommand(Vec<CommandDataOption>),
+    SubCommandGroup(Vec<CommandDataOption>),
+    String(String),
+    Integer(i64),
+    Boolean(bool),
+    User(Snowflake),
+    Channel(Snowflake),
+    Role(Snowflake),
+    Mentionable(Snowflake),
+    Number(f64),
+    Attachment(Snowflake),
+    /// The partial input of the focused option in an autocomplete interaction, which
+    /// Discord sends as typed so far regardless of the option's declared type.
+    Focused(String, CommandOptionType),
+}
+
+impl CommandOptionValue {
+    pub fn kind(&self) -> CommandOptionType {
+        match self {
+            CommandOptionValue::SubCommand(_) => CommandOptionType::SubCommand,
+            CommandOptionValue::SubCommandGroup(_) => CommandOptionType::SubCommandGroup,
+            CommandOptionValue::String(_) => CommandOptionType::String,
+            CommandOptionValue::Integer(_) => CommandOptionType::Integer,
+            CommandOptionValue::Boolean(_) => CommandOptionType::Boolean,
+            CommandOptionValue::User(_) => CommandOptionType::User,
+            CommandOptionValue::Channel(_) => CommandOptionType::Channel,
+            CommandOptionValue::Role(_) => CommandOptionType::Role,
+            CommandOptionValue::Mentionable(_) => CommandOptionType::Mentionable,
+            CommandOptionValue::Number(_) => CommandOptionType::Number,
+            CommandOptionValue::Attachment(_) => CommandOptionType::Attachment,
+            CommandOptionValue::Focused(_, kind) => *kind,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RawCommandDataOption {
+    name: String,
+    #[serde(rename = "type")]
+    option_type: CommandOptionType,
+    value: Option<Value>,
+    #[serde(default)]
+    options: Vec<CommandDataOption>,
+    #[serde(default)]
+    focused: bool,
+}
+
+impl RawCommandDataOption {
+    fn into_value(self) -> Result<CommandOptionValue, serde_json::Error> {
+        if self.focused {
+            let partial = match self.value {
+                Some(Value::String(partial)) => partial,
+                Some(other) => other.to_string(),
+                None => String::new(),
+            };
+            return Ok(CommandOptionValue::Focused(partial, self.option_type));
+        }
+
+        let value = match self.option_type {
+            CommandOptionType::SubCommand => return Ok(CommandOptionValue::SubCommand(self.options)),
+            CommandOptionType::SubCommandGroup => {
+                return Ok(CommandOptionValue::SubCommandGroup(self.options))
+            }
+            _ => self
+                .value
+                .ok_or_else(|| serde_json::Error::missing_field("value"))?,
+        };
+
+        Ok(match self.option_type {
+            CommandOptionType::String => CommandOptionValue::String(serde_json::from_value(value)?),
+            CommandOptionType::Integer => CommandOptionValue::Integer(serde_json::from_value(value)?),
+            CommandOptionType::Boolean => CommandOptionValue::Boolean(serde_json::from_value(value)?),
+            CommandOptionType::User => CommandOptionValue::User(serde_json::from_value(value)?),
+            CommandOptionType::Channel => CommandOptionValue::Channel(serde_json::from_value(value)?),
+            CommandOptionType::Role => CommandOptionValue::Role(serde_json::from_value(value)?),
+            CommandOptionType::Mentionable => {
+                CommandOptionValue::Mentionable(serde_json::from_value(value)?)
+            }
+            CommandOptionType::Number => CommandOptionValue::Number(serde_json::from_value(value)?),
+            CommandOptionType::Attachment => {
+                CommandOptionValue::Attachment(serde_json::from_value(value)?)
+            }
+            CommandOptionType::SubCommand | CommandOptionType::SubCommandGroup => unreachable!(),
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for CommandDataOption {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw = RawCommandDataOption::deserialize(deserializer)?;
+        let name = raw.name.clone();
+        let value = raw.into_value().map_err(D::Error::custom)?;
+        Ok(CommandDataOption { name, value })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ApplicationCommandType {
+    ChatInput,
+    User,
+    Message,
+    PrimaryEntryPoint,
+}
+
+impl<'de> Deserialize<'de> for ApplicationCommandType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: u8 = Deserialize::deserialize(deserializer)?;
+        match value {
+            1 => Ok(ApplicationCommandType::ChatInput),
+            2 => Ok(ApplicationCommandType::User),
+            3 => Ok(ApplicationCommandType::Message),
+            4 => Ok(ApplicationCommandType::PrimaryEntryPoint),
+            _ => Err(serde::de::Error::custom("Unknown application command type")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandOptionType {
+    SubCommand,
+    SubCommandGroup,
+    String,
+    Integer,
+    Boolean,
+    User,
+    Channel,
+    Role,
+    Mentionable,
+    Number,
+    Attachment,
+}
+
+impl<'de> Deserialize<'de> for CommandOptionType {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value: u8 = Deserialize::deserialize(deserializer)?;
+        match value {
+            1 => Ok(CommandOptionType::SubCommand),
+            2 => Ok(CommandOptionType::SubCommandGroup),
+            3 => Ok(CommandOptionType::String),
+            4 => Ok(CommandOptionType::Integer),
+            5 => Ok(CommandOptionType::Boolean),
+            6 => Ok(CommandOptionType::User),
+            7 => Ok(CommandOptionType::Channel),
+            8 => Ok(CommandOptionType::Role),
+            9 => Ok(CommandOptionType::Mentionable),
+            10 => Ok(CommandOptionType::Number),
+            11 => Ok(CommandOptionType::Attachment),
+            _ => Err(serde::de::Error::custom("Unknown command option type")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ApplicationCommandData, CommandOptionType, CommandOptionValue};
+
+    #[test]
+    fn deserialize_typed_options() {
+        let data = r#"{"id":"1","name":"ban","type":1,"guild_id":"7","options":[{"name":"target","type":6,"value":"643945264868098049"},{"name":"days","type":4,"value":7},{"name":"ratio","type":10,"value":0.5},{"name":"silent","type":5,"value":true},{"name":"reason","type":3,"value":"spam"}]}"#;
+
+        let data: ApplicationCommandData = serde_json::from_str(data).unwrap();
+
+        let values: Vec<_> = data.options.iter().map(|option| &option.value).collect();
+        assert!(matches!(values[0], CommandOptionValue::User(id) if id.to_string() == "643945264868098049"));
+        assert_eq!(values[1], &CommandOptionValue::Integer(7));
+        assert_eq!(values[2], &CommandOptionValue::Number(0.5));
+        assert_eq!(values[3], &CommandOptionValue::Boolean(true));
+        assert_eq!(values[4], &CommandOptionValue::String("spam".to_string()));
+    }
+
+    #[test]
+    fn deserialize_focused_option() {
+        let data = r#"{"id":"1","name":"count","type":1,"options":[{"name":"amount","type":4,"value":"12","focused":true}]}"#;
+
+        let data: ApplicationCommandData = serde_json::from_str(data).unwrap();
+
+        assert!(data.options[0].is_focused());
+        assert_eq!(
+            data.options[0].value,
+            CommandOptionValue::Focused("12".to_string(), CommandOptionType::Integer)
+        );
+    }
+
+    #[test]
+    fn reject_mistyped_value() {
+        let data = r#"{"id":"1","name":"count","type":1,"options":[{"name":"amount","type":4,"value":"twelve"}]}"#;
+        assert!(serde_json::from_str::<ApplicationCommandData>(data).is_err());
+    }
+}
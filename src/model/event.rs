@@ -0,0 +1,237 @@
+use crate::model::primitives::Snowflake;
+use crate::model::user::User;
+use serde::de::{DeserializeOwned, Error};
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// An incoming webhook event payload, dispatched on its `type` field.
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum WebhookEvent {
+    /// Sent by Discord to check the endpoint when it is configured.
+    Ping(WebhookPing),
+    Event(EventWebhook),
+}
+
+impl WebhookEvent {
+    pub fn application_id(&self) -> &Snowflake {
+        match self {
+            WebhookEvent::Ping(ping) => &ping.application_id,
+            WebhookEvent::Event(event) => &event.application_id,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for WebhookEvent {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let webhook_type: u8 = value
+            .get("type")
+            .ok_or_else(|| D::Error::missing_field("type"))
+            .and_then(|t| u8::deserialize(t).map_err(D::Error::custom))?;
+
+        let event = match webhook_type {
+            0 => WebhookPing::deserialize(value).map(WebhookEvent::Ping),
+            1 => EventWebhook::deserialize(value).map(WebhookEvent::Event),
+            _ => return Err(D::Error::custom("Unknown webhook type")),
+        };
+
+        event.map_err(D::Error::custom)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct WebhookPing {
+    pub version: u8,
+    pub application_id: Snowflake,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EventWebhook {
+    pub version: u8,
+    pub application_id: Snowflake,
+    pub event: Event,
+}
+
+#[derive(Debug)]
+pub struct Event {
+    /// When the event occurred, as an ISO8601 timestamp.
+    pub timestamp: String,
+    pub data: EventData,
+}
+
+#[derive(Deserialize)]
+struct RawEvent {
+    #[serde(rename = "type")]
+    event_type: String,
+    timestamp: String,
+    #[serde(default)]
+    data: Value,
+}
+
+impl<'de> Deserialize<'de> for Event {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let RawEvent {
+            event_type,
+            timestamp,
+            data,
+        } = RawEvent::deserialize(deserializer)?;
+        let data = match event_type.as_str() {
+            "APPLICATION_AUTHORIZED" => decode(event_type, data, EventData::ApplicationAuthorized),
+            "APPLICATION_DEAUTHORIZED" => {
+                decode(event_type, data, EventData::ApplicationDeauthorized)
+            }
+            "ENTITLEMENT_CREATE" => decode(event_type, data, EventData::EntitlementCreate),
+            "QUEST_USER_ENROLLMENT" => EventData::QuestUserEnrollment(data),
+            _ => EventData::Unknown(event_type),
+        };
+
+        Ok(Event { timestamp, data })
+    }
+}
+
+/// Decodes the `data` of a known event type, keeping it untyped if its shape does not match,
+/// so one unexpected payload does not fail the whole webhook.
+fn decode<T: DeserializeOwned>(
+    event_type: String,
+    data: Value,
+    variant: fn(T) -> EventData,
+) -> EventData {
+    match T::deserialize(&data) {
+        Ok(decoded) => variant(decoded),
+        Err(_) => EventData::Undecoded { event_type, data },
+    }
+}
+
+/// The `type` and `data` of an event.
+#[derive(Debug)]
+pub enum EventData {
+    ApplicationAuthorized(ApplicationAuthorized),
+    ApplicationDeauthorized(ApplicationDeauthorized),
+    EntitlementCreate(Entitlement),
+    /// Discord does not document this payload.
+    QuestUserEnrollment(Value),
+    /// An event type this crate does not model yet, by name.
+    Unknown(String),
+    /// A known event type whose `data` did not match its model, kept as raw JSON.
+    Undecoded { event_type: String, data: Value },
+}
+
+impl EventData {
+    pub fn event_type(&self) -> Option<EventType> {
+        match self {
+            EventData::ApplicationAuthorized(_) => Some(EventType::ApplicationAuthorized),
+            EventData::ApplicationDeauthorized(_) => Some(EventType::ApplicationDeauthorized),
+            EventData::EntitlementCreate(_) => Some(EventType::EntitlementCreate),
+            EventData::QuestUserEnrollment(_) => Some(EventType::QuestUserEnrollment),
+            EventData::Unknown(_) | EventData::Undecoded { .. } => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventType {
+    ApplicationAuthorized,
+    ApplicationDeauthorized,
+    EntitlementCreate,
+    QuestUserEnrollment,
+}
+
+/// Sent when the application is added to a server or a user account.
+#[derive(Debug, Deserialize)]
+pub struct ApplicationAuthorized {
+    /// `0` when installed to a guild, `1` when installed to a user.
+    pub integration_type: Option<u8>,
+    pub user: User,
+    pub scopes: Vec<String>,
+    /// The guild the application was added to, when installed to a guild.
+    pub guild: Option<Value>,
+}
+
+/// Sent when the application is removed from a user account.
+#[derive(Debug, Deserialize)]
+pub struct ApplicationDeauthorized {
+    pub user: User,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Entitlement {
+    pub id: Snowflake,
+    pub sku_id: Snowflake,
+    pub application_id: Snowflake,
+    pub user_id: Option<Snowflake>,
+    #[serde(rename = "type")]
+    pub entitlement_type: u8,
+    pub deleted: bool,
+    pub starts_at: Option<String>,
+    pub ends_at: Option<String>,
+    pub guild_id: Option<Snowflake>,
+    pub consumed: Option<bool>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventData, EventType, WebhookEvent};
+
+    #[test]
+    fn deserialize_ping() {
+        let body = r#"{"version":1,"application_id":"1096551423958855810","type":0}"#;
+
+        let event: WebhookEvent = serde_json::from_str(body).unwrap();
+
+        assert!(matches!(event, WebhookEvent::Ping(_)));
+    }
+
+    #[test]
+    fn deserialize_application_authorized() {
+        let body = r#"{"version":1,"application_id":"1096551423958855810","type":1,"event":{"type":"APPLICATION_AUTHORIZED","timestamp":"2024-10-18T14:42:53.064834","data":{"integration_type":1,"scopes":["applications.commands"],"user":{"avatar":null,"discriminator":"0","global_name":"Someone","id":"643945264868098049","public_flags":0,"username":"someone"}}}}"#;
+
+        let event: WebhookEvent = serde_json::from_str(body).unwrap();
+
+        let WebhookEvent::Event(webhook) = event else {
+            panic!("Expected an event, got {:?}", event);
+        };
+        assert_eq!(webhook.event.data.event_type(), Some(EventType::ApplicationAuthorized));
+        match webhook.event.data {
+            EventData::ApplicationAuthorized(authorized) => {
+                assert_eq!(authorized.integration_type, Some(1));
+                assert_eq!(authorized.scopes, vec!["applications.commands"]);
+                assert_eq!(authorized.user.username, "someone");
+            }
+            other => panic!("Expected an authorization, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_unknown_event_type() {
+        let body = r#"{"version":1,"application_id":"1096551423958855810","type":1,"event":{"type":"SOMETHING_NEW","timestamp":"2024-10-18T14:42:53.064834","data":{"anything":true}}}"#;
+
+        let event: WebhookEvent = serde_json::from_str(body).unwrap();
+
+        let WebhookEvent::Event(webhook) = event else {
+            panic!("Expected an event, got {:?}", event);
+        };
+        assert!(matches!(webhook.event.data, EventData::Unknown(name) if name == "SOMETHING_NEW"));
+    }
+
+    #[test]
+    fn keep_undecodable_event_data() {
+        let body = r#"{"version":1,"application_id":"1096551423958855810","type":1,"event":{"type":"ENTITLEMENT_CREATE","timestamp":"2024-10-18T14:42:53.064834","data":{"id":42}}}"#;
+
+        let event: WebhookEvent = serde_json::from_str(body).unwrap();
+
+        let WebhookEvent::Event(webhook) = event else {
+            panic!("Expected an event, got {:?}", event);
+        };
+        assert!(matches!(
+            webhook.event.data,
+            EventData::Undecoded { event_type, data } if event_type == "ENTITLEMENT_CREATE" && data["id"] == 42
+        ));
+    }
+}
@@ -52,8 +52,10 @@ pub struct GuildMember {
     pub roles: Vec<String>,            // Array of role object IDs
     pub joined_at: String,             // When the user joined the guild
     pub premium_since: Option<String>, // When the user started boosting the guild
-    pub deaf: bool,                    // Whether the user is deafened in voice channels
-    pub mute: bool,                    // Whether the user is muted in voice channels
+    #[serde(default)]
+    pub deaf: bool,                    // Whether the user is deafened in voice channels (absent on partial members)
+    #[serde(default)]
+    pub mute: bool,                    // Whether the user is muted in voice channels (absent on partial members)
     pub pending: Option<bool>, // Whether the user has not yet passed the guild's Membership Screening requirements
     pub permissions: Option<String>, // Total permissions of the member in the channel, including overwrites, returned when in the interaction object
     pub communication_disabled_until: Option<String>, // When the user's timeout will expire and the user will be able to communicate in the guild again
@@ -1,72 +1,178 @@
+use crate::model::application_command::ApplicationCommandData;
 use crate::model::channel::Channel;
 use crate::model::guild::GuildMember;
-use crate::model::message::Message;
+use crate::model::message::{Component, Message};
 use crate::model::primitives::Snowflake;
+use crate::model::resolved::Resolved;
 use crate::model::user::User;
+use serde::de::Error;
 use serde::{Deserialize, Deserializer};
 use serde_json::Value;
 
-#[derive(Debug, Deserialize)]
-pub struct FullInteraction {
-    id: Snowflake,
-    application_id: Snowflake,
-    #[serde(rename = "type")]
-    #[serde(deserialize_with = "InteractionType::deserialize")]
-    interaction_type: InteractionType,
-    data: Option<Value>,
-    guild: Option<Value>,
-    guild_id: Option<Snowflake>,
-    channel: Option<Channel>,
-    channel_id: Option<Snowflake>,
-    member: Option<GuildMember>,
-    user: Option<User>,
-    token: String,
-    version: u8,
-    message: Option<Message>,
-    app_permissions: String,
-    locale: Option<String>,
-    guild_locale: Option<String>,
-    entitlements: Vec<Value>,
-    authorizing_integration_owners: Value,
-    context: Option<Value>,
-    attachment_size_limit: u64,
+/// An incoming interaction, dispatched on its `type` field.
+///
+/// Each variant only carries the fields Discord sends for that interaction type.
+#[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum Interaction {
+    Ping(PingInteraction),
+    ApplicationCommand(CommandInteraction),
+    MessageComponent(ComponentInteraction),
+    Autocomplete(CommandInteraction),
+    ModalSubmit(ModalSubmitInteraction),
+}
+
+impl Interaction {
+    pub fn interaction_type(&self) -> InteractionType {
+        match self {
+            Interaction::Ping(_) => InteractionType::Ping,
+            Interaction::ApplicationCommand(_) => InteractionType::ApplicationCommand,
+            Interaction::MessageComponent(_) => InteractionType::MessageComponent,
+            Interaction::Autocomplete(_) => InteractionType::ApplicationCommandAutocomplete,
+            Interaction::ModalSubmit(_) => InteractionType::ModalSubmit,
+        }
+    }
+
+    pub fn id(&self) -> &Snowflake {
+        match self {
+            Interaction::Ping(ping) => &ping.id,
+            Interaction::ApplicationCommand(command) | Interaction::Autocomplete(command) => {
+                &command.base.id
+            }
+            Interaction::MessageComponent(component) => &component.base.id,
+            Interaction::ModalSubmit(modal) => &modal.base.id,
+        }
+    }
+
+    pub fn application_id(&self) -> &Snowflake {
+        match self {
+            Interaction::Ping(ping) => &ping.application_id,
+            Interaction::ApplicationCommand(command) | Interaction::Autocomplete(command) => {
+                &command.base.application_id
+            }
+            Interaction::MessageComponent(component) => &component.base.application_id,
+            Interaction::ModalSubmit(modal) => &modal.base.application_id,
+        }
+    }
+
+    pub fn token(&self) -> &str {
+        match self {
+            Interaction::Ping(ping) => &ping.token,
+            Interaction::ApplicationCommand(command) | Interaction::Autocomplete(command) => {
+                &command.base.token
+            }
+            Interaction::MessageComponent(component) => &component.base.token,
+            Interaction::ModalSubmit(modal) => &modal.base.token,
+        }
+    }
 }
 
+impl<'de> Deserialize<'de> for Interaction {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = Value::deserialize(deserializer)?;
+        let interaction_type = value
+            .get("type")
+            .ok_or_else(|| D::Error::missing_field("type"))
+            .and_then(|t| InteractionType::deserialize(t).map_err(D::Error::custom))?;
+
+        let interaction = match interaction_type {
+            InteractionType::Ping => PingInteraction::deserialize(value).map(Interaction::Ping),
+            InteractionType::ApplicationCommand => {
+                CommandInteraction::deserialize(value).map(Interaction::ApplicationCommand)
+            }
+            InteractionType::MessageComponent => {
+                ComponentInteraction::deserialize(value).map(Interaction::MessageComponent)
+            }
+            InteractionType::ApplicationCommandAutocomplete => {
+                CommandInteraction::deserialize(value).map(Interaction::Autocomplete)
+            }
+            InteractionType::ModalSubmit => {
+                ModalSubmitInteraction::deserialize(value).map(Interaction::ModalSubmit)
+            }
+        };
+
+        interaction.map_err(D::Error::custom)
+    }
+}
+
+/// Fields shared by every interaction type except `PING`.
 #[derive(Debug, Deserialize)]
-#[serde(untagged)]
-#[allow(clippy::large_enum_variant)]
-pub enum Interaction {
-    // Ping(PingInteraction),
-    // GuildCommand(GuildCommandInteraction),
-    Full(FullInteraction),
+pub struct InteractionBase {
+    pub id: Snowflake,
+    pub application_id: Snowflake,
+    pub guild: Option<Value>,
+    pub guild_id: Option<Snowflake>,
+    pub channel: Option<Channel>,
+    pub channel_id: Option<Snowflake>,
+    pub member: Option<GuildMember>,
+    pub user: Option<User>,
+    pub token: String,
+    pub version: u8,
+    pub app_permissions: String,
+    pub locale: Option<String>,
+    pub guild_locale: Option<String>,
+    #[serde(default)]
+    pub entitlements: Vec<Value>,
+    pub authorizing_integration_owners: Value,
+    pub context: Option<Value>,
+    pub attachment_size_limit: u64,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct PingInteraction {
-    id: Snowflake,
-    application_id: Snowflake,
-    #[serde(rename = "type")]
-    #[serde(deserialize_with = "InteractionType::deserialize")]
-    interaction_type: InteractionType,
-    user: User,
-    token: String,
-    version: u8,
-    app_permissions: String,
-    authorizing_integration_owners: Value,
-    attachment_size_limit: u64,
+    pub id: Snowflake,
+    pub application_id: Snowflake,
+    pub token: String,
+    pub version: u8,
+    pub user: Option<User>,
 }
 
+/// An `APPLICATION_COMMAND` or `APPLICATION_COMMAND_AUTOCOMPLETE` interaction.
 #[derive(Debug, Deserialize)]
-pub struct GuildCommandInteraction {
-    id: Snowflake,
-    application_id: Snowflake,
-    #[serde(rename = "type")]
-    #[serde(deserialize_with = "InteractionType::deserialize")]
-    interaction_type: InteractionType,
-    data: Option<Value>,
+pub struct CommandInteraction {
+    #[serde(flatten)]
+    pub base: InteractionBase,
+    pub data: ApplicationCommandData,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize)]
+pub struct ComponentInteraction {
+    #[serde(flatten)]
+    pub base: InteractionBase,
+    pub data: MessageComponentData,
+    pub message: Message,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ModalSubmitInteraction {
+    #[serde(flatten)]
+    pub base: InteractionBase,
+    pub data: ModalSubmitData,
+    pub message: Option<Message>,
+}
+
+/// The `data` payload of a `MESSAGE_COMPONENT` interaction.
+#[derive(Debug, Clone, Deserialize)]
+pub struct MessageComponentData {
+    pub custom_id: String,
+    pub component_type: i32,
+    /// The selected option values, for select menus.
+    #[serde(default)]
+    pub values: Vec<String>,
+    pub resolved: Option<Resolved>,
+}
+
+/// The `data` payload of a `MODAL_SUBMIT` interaction.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModalSubmitData {
+    pub custom_id: String,
+    pub components: Vec<Component>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum InteractionType {
     Ping,
     ApplicationCommand,
@@ -91,3 +197,101 @@ impl<'de> Deserialize<'de> for InteractionType {
         }
     }
 }
+
+/// Builds interaction payloads for tests, filling in the fields every interaction carries.
+///
+/// Component interactions also get the message holding the component.
+#[cfg(test)]
+pub(crate) struct TestInteraction {
+    body: Value,
+}
+
+#[cfg(test)]
+impl TestInteraction {
+    pub(crate) fn new(interaction_type: u8, data: Value) -> Self {
+        let mut body = serde_json::json!({
+            "app_permissions": "0",
+            "application_id": "1096551423958855810",
+            "attachment_size_limit": 524288000,
+            "authorizing_integration_owners": {},
+            "data": data,
+            "id": "2",
+            "token": "example_token",
+            "type": interaction_type,
+            "version": 1
+        });
+        if interaction_type == 3 {
+            body["message"] = crate::rest::tests::message("3", "");
+        }
+        TestInteraction { body }
+    }
+
+    /// Sets the interaction ID, which also determines when its token expires.
+    pub(crate) fn id(mut self, id: u64) -> Self {
+        self.body["id"] = Value::String(id.to_string());
+        self
+    }
+
+    pub(crate) fn json(&self) -> String {
+        self.body.to_string()
+    }
+
+    pub(crate) fn build(&self) -> Interaction {
+        serde_json::from_value(self.body.clone()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interaction, InteractionType};
+    use crate::model::application_command::ApplicationCommandType;
+
+    #[test]
+    fn deserialize_ping() {
+        let body = r#"{"app_permissions":"562949953601536","application_id":"1096551423958855810","attachment_size_limit":524288000,"authorizing_integration_owners":{},"entitlements":[],"id":"0000000001","token":"example_token","type":1,"user":{"avatar":"c6a249645d46209f337279cd2ca998c7","avatar_decoration_data":null,"bot":true,"clan":null,"collectibles":null,"discriminator":"0000","global_name":"Discord","id":"643945264868098049","primary_guild":null,"public_flags":1,"system":true,"username":"discord"},"version":1}"#;
+
+        let interaction: Interaction = serde_json::from_str(body).unwrap();
+
+        assert!(matches!(interaction, Interaction::Ping(_)));
+        assert_eq!(interaction.token(), "example_token");
+    }
+
+    #[test]
+    fn deserialize_application_command() {
+        let body = r#"{"app_permissions":"562949953601536","application_id":"1096551423958855810","attachment_size_limit":524288000,"authorizing_integration_owners":{"1":"643945264868098049"},"channel_id":"1096551424600576021","context":1,"data":{"id":"1371622100000000000","name":"ping","type":1},"entitlements":[],"id":"1371622186951114924","locale":"en-GB","token":"example_token","type":2,"user":{"avatar":null,"discriminator":"0","global_name":"Someone","id":"643945264868098049","public_flags":0,"username":"someone"},"version":1}"#;
+
+        let interaction: Interaction = serde_json::from_str(body).unwrap();
+
+        assert_eq!(interaction.interaction_type(), InteractionType::ApplicationCommand);
+        match interaction {
+            Interaction::ApplicationCommand(command) => {
+                assert_eq!(command.base.locale.as_deref(), Some("en-GB"));
+                assert_eq!(command.data.name, "ping");
+                assert_eq!(command.data.command_type, ApplicationCommandType::ChatInput);
+            }
+            other => panic!("Expected an application command, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn deserialize_modal_submit() {
+        let body = r#"{"app_permissions":"0","application_id":"1096551423958855810","attachment_size_limit":524288000,"authorizing_integration_owners":{},"data":{"custom_id":"feedback","components":[{"type":1,"components":[{"type":4,"custom_id":"summary","value":"Great bot"}]}]},"id":"2","token":"example_token","type":5,"version":1}"#;
+
+        let interaction: Interaction = serde_json::from_str(body).unwrap();
+
+        match interaction {
+            Interaction::ModalSubmit(modal) => {
+                assert_eq!(modal.data.custom_id, "feedback");
+                let input = &modal.data.components[0].components.as_ref().unwrap()[0];
+                assert_eq!(input.value.as_deref(), Some("Great bot"));
+            }
+            other => panic!("Expected a modal submit, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reject_unknown_type() {
+        let result = serde_json::from_str::<Interaction>(r#"{"type":42}"#);
+        assert!(result.is_err());
+    }
+}
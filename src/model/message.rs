@@ -38,72 +38,105 @@ pub struct Message {
     pub position: Option<i32>,   // The position of the message in the channel
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Embed {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub title: Option<String>, // Title of embed
     #[serde(rename = "type")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub embed_type: Option<String>, // Type of embed (always "rich" for webhook embeds)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>, // Description of embed
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,   // URL of embed
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<String>, // ISO8601 timestamp: timestamp of embed content
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub color: Option<i32>,    // Color code of embed
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub footer: Option<EmbedFooter>, // Embed footer object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub image: Option<EmbedImage>, // Embed image object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub thumbnail: Option<EmbedThumbnail>, // Embed thumbnail object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub video: Option<EmbedVideo>, // Embed video object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub provider: Option<EmbedProvider>, // Embed provider object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<EmbedAuthor>, // Embed author object
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub fields: Option<Vec<EmbedField>>, // Array of embed field objects
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedProvider {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>, // Name of provider
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,  // URL of provider
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedAuthor {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,           // Name of author
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,            // URL of author
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub icon_url: Option<String>, // URL of author icon (only supports http(s) and discord.com)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_icon_url: Option<String>, // A proxied URL of author icon
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedField {
     pub name: String,         // Name of field
     pub value: String,        // Value of field
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub inline: Option<bool>, // Whether this field should display inline
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedFooter {
     pub text: String,                   // Footer text
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub icon_url: Option<String>, // URL of footer icon (only supports http(s) and discord.com)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_icon_url: Option<String>, // A proxied URL of footer icon
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedImage {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>, // Source URL of image (only supports http(s) and discord.com)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_url: Option<String>, // A proxied URL of the image
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<i32>, // Height of image
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<i32>,  // Width of image
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedThumbnail {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>, // Source URL of thumbnail (only supports http(s) and discord.com)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub proxy_url: Option<String>, // A proxied URL of the thumbnail
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<i32>, // Height of thumbnail
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<i32>,  // Width of thumbnail
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct EmbedVideo {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>, // Source URL of video
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub height: Option<i32>, // Height of video
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub width: Option<i32>,  // Width of video
 }
 
@@ -147,35 +180,61 @@ pub struct Reaction {
     pub emoji: ReactionEmoji, // Reaction emoji object
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct ReactionEmoji {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub id: Option<String>, // Snowflake: The ID of the emoji (or null if a standard Unicode emoji)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>, // The name of the emoji
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Component {
     #[serde(rename = "type")]
     pub component_type: i32, // Component type
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub label: Option<String>, // Text that appears on the button (if type is Button)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub style: Option<i32>,    // A button style (if type is Button)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_id: Option<String>, // A developer-defined identifier for the component (if type is Button, SelectMenu)
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub url: Option<String>,       // A URL for link-style buttons
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub disabled: Option<bool>,    // Whether the component is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub components: Option<Vec<Component>>, // A list of child components
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub emoji: Option<ReactionEmoji>, // The emoji to display on the component
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub min_values: Option<i32>,   // The minimum number of values that must be chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub max_values: Option<i32>,   // The maximum number of values that can be chosen
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub options: Option<Vec<SelectOption>>, // A list of select menu options
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub placeholder: Option<String>, // A placeholder for the component
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,       // The pre-filled or submitted value of a text input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_length: Option<i32>,     // The minimum input length for a text input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_length: Option<i32>,     // The maximum input length for a text input
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub required: Option<bool>,      // Whether a text input must be filled in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub component: Option<Box<Component>>, // The single child of a label component
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct SelectOption {
     pub label: String,                // The user-facing name of the option
     pub value: String,                // The developer-defined value of the option
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,  // An additional description of the option
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub emoji: Option<ReactionEmoji>, // The emoji to display on the option
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub default: Option<bool>,        // Whether this option is selected by default
 }
 
@@ -1,6 +1,11 @@
 pub mod interactions;
 pub mod primitives;
-mod guild;
-mod message;
-mod user;
-mod channel;
\ No newline at end of file
+pub mod application_command;
+pub mod guild;
+pub mod message;
+pub mod modal;
+pub mod resolved;
+pub mod response;
+pub mod user;
+pub mod channel;
+pub mod event;
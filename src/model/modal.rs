@@ -0,0 +1,307 @@
+use crate::model::message::Component;
+use crate::model::response::ModalData;
+use std::fmt::{Display, Formatter};
+
+pub const MAX_CUSTOM_ID_LENGTH: usize = 100;
+pub const MAX_TITLE_LENGTH: usize = 45;
+pub const MAX_INPUTS: usize = 5;
+pub const MAX_LABEL_LENGTH: usize = 45;
+pub const MAX_PLACEHOLDER_LENGTH: usize = 100;
+pub const MAX_VALUE_LENGTH: usize = 4000;
+
+const ACTION_ROW: i32 = 1;
+const TEXT_INPUT: i32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextInputStyle {
+    /// A single-line input.
+    Short,
+    /// A multi-line input.
+    Paragraph,
+}
+
+/// A text input shown in a modal.
+#[derive(Debug, Clone)]
+pub struct TextInput {
+    custom_id: String,
+    label: String,
+    style: TextInputStyle,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    required: Option<bool>,
+    value: Option<String>,
+    placeholder: Option<String>,
+}
+
+impl TextInput {
+    pub fn new(custom_id: impl Into<String>, label: impl Into<String>, style: TextInputStyle) -> Self {
+        TextInput {
+            custom_id: custom_id.into(),
+            label: label.into(),
+            style,
+            min_length: None,
+            max_length: None,
+            required: None,
+            value: None,
+            placeholder: None,
+        }
+    }
+
+    pub fn short(custom_id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self::new(custom_id, label, TextInputStyle::Short)
+    }
+
+    pub fn paragraph(custom_id: impl Into<String>, label: impl Into<String>) -> Self {
+        Self::new(custom_id, label, TextInputStyle::Paragraph)
+    }
+
+    pub fn min_length(mut self, min_length: usize) -> Self {
+        self.min_length = Some(min_length);
+        self
+    }
+
+    pub fn max_length(mut self, max_length: usize) -> Self {
+        self.max_length = Some(max_length);
+        self
+    }
+
+    pub fn required(mut self, required: bool) -> Self {
+        self.required = Some(required);
+        self
+    }
+
+    /// Pre-fills the input.
+    pub fn value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    fn validate(&self) -> Result<(), ModalError> {
+        check_length("text input custom_id", &self.custom_id, 1, MAX_CUSTOM_ID_LENGTH)?;
+        check_length("text input label", &self.label, 1, MAX_LABEL_LENGTH)?;
+        if let Some(placeholder) = &self.placeholder {
+            check_length("text input placeholder", placeholder, 0, MAX_PLACEHOLDER_LENGTH)?;
+        }
+        if let Some(min_length) = self.min_length {
+            check_bound("text input min_length", min_length, 0, MAX_VALUE_LENGTH)?;
+        }
+        if let Some(max_length) = self.max_length {
+            check_bound("text input max_length", max_length, 1, MAX_VALUE_LENGTH)?;
+        }
+
+        let min_length = self.min_length.unwrap_or(0);
+        let max_length = self.max_length.unwrap_or(MAX_VALUE_LENGTH);
+        if min_length > max_length {
+            return Err(ModalError::MinLengthExceedsMaxLength {
+                custom_id: self.custom_id.clone(),
+            });
+        }
+        if let Some(value) = &self.value {
+            check_length("text input value", value, 0, max_length)?;
+        }
+
+        Ok(())
+    }
+
+    fn into_component(self) -> Component {
+        let style = match self.style {
+            TextInputStyle::Short => 1,
+            TextInputStyle::Paragraph => 2,
+        };
+
+        Component {
+            component_type: TEXT_INPUT,
+            custom_id: Some(self.custom_id),
+            label: Some(self.label),
+            style: Some(style),
+            min_length: self.min_length.map(|length| length as i32),
+            max_length: self.max_length.map(|length| length as i32),
+            required: self.required,
+            value: self.value,
+            placeholder: self.placeholder,
+            ..Component::default()
+        }
+    }
+}
+
+/// Builds the data of a `MODAL` response, validated against Discord's limits.
+#[derive(Debug, Clone)]
+pub struct ModalBuilder {
+    custom_id: String,
+    title: String,
+    inputs: Vec<TextInput>,
+}
+
+impl ModalBuilder {
+    pub fn new(custom_id: impl Into<String>, title: impl Into<String>) -> Self {
+        ModalBuilder {
+            custom_id: custom_id.into(),
+            title: title.into(),
+            inputs: Vec::new(),
+        }
+    }
+
+    pub fn text_input(mut self, input: TextInput) -> Self {
+        self.inputs.push(input);
+        self
+    }
+
+    /// Validates the modal and lays each text input out in its own action row.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any length, count or range exceeds Discord's limits.
+    pub fn build(self) -> Result<ModalData, ModalError> {
+        check_length("modal custom_id", &self.custom_id, 1, MAX_CUSTOM_ID_LENGTH)?;
+        check_length("modal title", &self.title, 1, MAX_TITLE_LENGTH)?;
+        if self.inputs.is_empty() || self.inputs.len() > MAX_INPUTS {
+            return Err(ModalError::InputCount(self.inputs.len()));
+        }
+        for input in &self.inputs {
+            input.validate()?;
+        }
+
+        Ok(ModalData {
+            custom_id: self.custom_id,
+            title: self.title,
+            components: self
+                .inputs
+                .into_iter()
+                .map(|input| Component {
+                    component_type: ACTION_ROW,
+                    components: Some(vec![input.into_component()]),
+                    ..Component::default()
+                })
+                .collect(),
+        })
+    }
+}
+
+/// A modal that Discord would reject.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModalError {
+    /// A string or numeric field falls outside its allowed range.
+    OutOfRange {
+        field: &'static str,
+        min: usize,
+        max: usize,
+        actual: usize,
+    },
+    /// A modal must contain between 1 and 5 text inputs.
+    InputCount(usize),
+    MinLengthExceedsMaxLength { custom_id: String },
+}
+
+impl Display for ModalError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModalError::OutOfRange {
+                field,
+                min,
+                max,
+                actual,
+            } => write!(f, "The {} must be between {} and {}, got {}", field, min, max, actual),
+            ModalError::InputCount(count) => write!(
+                f,
+                "A modal must have between 1 and {} text inputs, got {}",
+                MAX_INPUTS, count
+            ),
+            ModalError::MinLengthExceedsMaxLength { custom_id } => write!(
+                f,
+                "Text input {} has a min_length greater than its max_length",
+                custom_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ModalError {}
+
+fn check_length(field: &'static str, value: &str, min: usize, max: usize) -> Result<(), ModalError> {
+    check_bound(field, value.chars().count(), min, max)
+}
+
+fn check_bound(field: &'static str, actual: usize, min: usize, max: usize) -> Result<(), ModalError> {
+    if (min..=max).contains(&actual) {
+        Ok(())
+    } else {
+        Err(ModalError::OutOfRange {
+            field,
+            min,
+            max,
+            actual,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModalBuilder, ModalError, TextInput};
+    use serde_json::json;
+
+    #[test]
+    fn build_modal() {
+        let modal = ModalBuilder::new("feedback", "Feedback")
+            .text_input(
+                TextInput::paragraph("summary", "Summary")
+                    .min_length(10)
+                    .max_length(500)
+                    .required(true)
+                    .placeholder("What happened?"),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&modal).unwrap(),
+            json!({
+                "custom_id": "feedback",
+                "title": "Feedback",
+                "components": [{
+                    "type": 1,
+                    "components": [{
+                        "type": 4,
+                        "custom_id": "summary",
+                        "label": "Summary",
+                        "style": 2,
+                        "min_length": 10,
+                        "max_length": 500,
+                        "required": true,
+                        "placeholder": "What happened?"
+                    }]
+                }]
+            })
+        );
+    }
+
+    #[test]
+    fn reject_invalid_modals() {
+        assert_eq!(
+            ModalBuilder::new("feedback", "Feedback").build().unwrap_err(),
+            ModalError::InputCount(0)
+        );
+        assert!(matches!(
+            ModalBuilder::new("feedback", "x".repeat(46))
+                .text_input(TextInput::short("name", "Name"))
+                .build(),
+            Err(ModalError::OutOfRange { field: "modal title", .. })
+        ));
+        assert!(matches!(
+            ModalBuilder::new("feedback", "Feedback")
+                .text_input(TextInput::short("name", "Name").min_length(20).max_length(10))
+                .build(),
+            Err(ModalError::MinLengthExceedsMaxLength { .. })
+        ));
+        assert!(matches!(
+            ModalBuilder::new("feedback", "Feedback")
+                .text_input(TextInput::short("name", "Name").max_length(4).value("too long"))
+                .build(),
+            Err(ModalError::OutOfRange { field: "text input value", .. })
+        ));
+    }
+}
@@ -1,15 +1,28 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+/// The first millisecond of 2015, which snowflake timestamps count from.
+pub const DISCORD_EPOCH_MILLIS: u64 = 1_420_070_400_000;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[serde(transparent)]
 pub struct Snowflake {
     id: String,
 }
 
 impl Snowflake {
-    fn get_id(&self) -> u64 {
-        self.id.parse().unwrap()
+    /// The ID as a number, or `None` if it is not numeric.
+    pub fn get_id(&self) -> Option<u64> {
+        self.id.parse().ok()
+    }
+
+    /// When the ID was generated, from the timestamp in its upper 42 bits.
+    ///
+    /// Returns `None` if the ID is not numeric.
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        let id = self.get_id()?;
+        Some(UNIX_EPOCH + Duration::from_millis((id >> 22) + DISCORD_EPOCH_MILLIS))
     }
 }
 
@@ -17,4 +30,27 @@ impl Display for Snowflake {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.id)
     }
-}
\ No newline at end of file
+}
+impl From<u64> for Snowflake {
+    fn from(id: u64) -> Self {
+        Snowflake { id: id.to_string() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Snowflake;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn read_snowflake_timestamp() {
+        let id = Snowflake::from(1371622186951114924);
+        let timestamp = id.timestamp().unwrap().duration_since(UNIX_EPOCH).unwrap();
+        assert_eq!(timestamp, Duration::from_millis(1747090612877));
+        assert_eq!(id.get_id(), Some(1371622186951114924));
+
+        let id: Snowflake = serde_json::from_str(r#""not-an-id""#).unwrap();
+        assert!(id.timestamp().is_none());
+        assert!(id.get_id().is_none());
+    }
+}
@@ -0,0 +1,120 @@
+use crate::model::application_command::CommandOptionValue;
+use crate::model::channel::Channel;
+use crate::model::guild::{GuildMember, Role};
+use crate::model::message::{Attachment, Message};
+use crate::model::primitives::Snowflake;
+use crate::model::user::User;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// The full objects behind the snowflakes referenced by an interaction's options,
+/// select menu values and context menu targets.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Resolved {
+    #[serde(default)]
+    pub users: HashMap<Snowflake, User>,
+    /// Partial members, keyed by user ID. These never carry `user`, `deaf` or `mute`.
+    #[serde(default)]
+    pub members: HashMap<Snowflake, GuildMember>,
+    #[serde(default)]
+    pub roles: HashMap<Snowflake, Role>,
+    #[serde(default)]
+    pub channels: HashMap<Snowflake, Channel>,
+    #[serde(default)]
+    pub messages: HashMap<Snowflake, Message>,
+    #[serde(default)]
+    pub attachments: HashMap<Snowflake, Attachment>,
+}
+
+/// A resolved object referenced by a command option.
+#[derive(Debug, Clone, Copy)]
+pub enum ResolvedValue<'a> {
+    /// A user, along with their guild member when the command was run in a guild.
+    User(&'a User, Option<&'a GuildMember>),
+    Role(&'a Role),
+    Channel(&'a Channel),
+    Attachment(&'a Attachment),
+}
+
+impl Resolved {
+    pub fn user(&self, id: &Snowflake) -> Option<&User> {
+        self.users.get(id)
+    }
+
+    pub fn member(&self, id: &Snowflake) -> Option<&GuildMember> {
+        self.members.get(id)
+    }
+
+    pub fn role(&self, id: &Snowflake) -> Option<&Role> {
+        self.roles.get(id)
+    }
+
+    pub fn channel(&self, id: &Snowflake) -> Option<&Channel> {
+        self.channels.get(id)
+    }
+
+    pub fn message(&self, id: &Snowflake) -> Option<&Message> {
+        self.messages.get(id)
+    }
+
+    pub fn attachment(&self, id: &Snowflake) -> Option<&Attachment> {
+        self.attachments.get(id)
+    }
+
+    /// Looks up the full object behind a `USER`, `ROLE`, `CHANNEL`, `MENTIONABLE` or
+    /// `ATTACHMENT` option value.
+    ///
+    /// Returns `None` for other option types or if Discord did not resolve the snowflake.
+    pub fn resolve(&self, value: &CommandOptionValue) -> Option<ResolvedValue<'_>> {
+        match value {
+            CommandOptionValue::User(id) => self.resolve_user(id),
+            CommandOptionValue::Role(id) => self.role(id).map(ResolvedValue::Role),
+            CommandOptionValue::Channel(id) => self.channel(id).map(ResolvedValue::Channel),
+            CommandOptionValue::Attachment(id) => {
+                self.attachment(id).map(ResolvedValue::Attachment)
+            }
+            CommandOptionValue::Mentionable(id) => self
+                .resolve_user(id)
+                .or_else(|| self.role(id).map(ResolvedValue::Role)),
+            _ => None,
+        }
+    }
+
+    fn resolve_user(&self, id: &Snowflake) -> Option<ResolvedValue<'_>> {
+        self.user(id).map(|user| ResolvedValue::User(user, self.member(id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Resolved, ResolvedValue};
+    use crate::model::application_command::CommandOptionValue;
+
+    const RESOLVED: &str = r#"{
+        "users":{"643945264868098049":{"id":"643945264868098049","username":"someone","discriminator":"0","global_name":null,"avatar":null}},
+        "members":{"643945264868098049":{"nick":"Some","avatar":null,"roles":["42"],"joined_at":"2023-01-01T00:00:00.000000+00:00","premium_since":null,"pending":false,"permissions":"8","communication_disabled_until":null}},
+        "roles":{"42":{"id":"42","name":"Moderator","color":0,"hoist":false,"icon":null,"unicode_emoji":null,"position":1,"permissions":"8","managed":false,"mentionable":true}}
+    }"#;
+
+    #[test]
+    fn resolve_option_values() {
+        let resolved: Resolved = serde_json::from_str(RESOLVED).unwrap();
+        let user_id = serde_json::from_str(r#""643945264868098049""#).unwrap();
+        let role_id = serde_json::from_str(r#""42""#).unwrap();
+
+        match resolved.resolve(&CommandOptionValue::User(user_id)) {
+            Some(ResolvedValue::User(user, Some(member))) => {
+                assert_eq!(user.username, "someone");
+                assert_eq!(member.nick.as_deref(), Some("Some"));
+            }
+            other => panic!("Expected a user with a member, got {:?}", other),
+        }
+
+        match resolved.resolve(&CommandOptionValue::Mentionable(role_id)) {
+            Some(ResolvedValue::Role(role)) => assert_eq!(role.name, "Moderator"),
+            other => panic!("Expected a role, got {:?}", other),
+        }
+
+        assert!(resolved.resolve(&CommandOptionValue::Integer(42)).is_none());
+    }
+}
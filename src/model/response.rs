@@ -0,0 +1,397 @@
+use crate::model::message::{Component, Embed};
+use crate::model::primitives::Snowflake;
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use std::ops::{BitOr, BitOrAssign};
+
+/// The body returned to Discord in reply to an interaction.
+#[derive(Debug, Clone)]
+pub enum InteractionResponse {
+    /// Acknowledges a `PING`.
+    Pong,
+    /// Responds to an interaction with a message.
+    ChannelMessageWithSource(InteractionCallbackData),
+    /// Acknowledges an interaction and edits a response later; the user sees a loading state.
+    /// Only the `EPHEMERAL` flag is honoured.
+    DeferredChannelMessageWithSource(Option<MessageFlags>),
+    /// For components, acknowledges an interaction and edits the original message later.
+    DeferredUpdateMessage,
+    /// For components, edits the message the component was attached to.
+    UpdateMessage(InteractionCallbackData),
+    /// Responds to an autocomplete interaction with suggested choices.
+    ApplicationCommandAutocompleteResult(Vec<CommandOptionChoice>),
+    /// Responds to an interaction with a popup modal.
+    Modal(ModalData),
+    /// Deprecated by Discord in favour of premium buttons.
+    PremiumRequired,
+    /// Launches the activity associated with the app.
+    LaunchActivity,
+}
+
+impl InteractionResponse {
+    /// Replies to the interaction with a plain text message.
+    pub fn message(content: impl Into<String>) -> Self {
+        InteractionResponse::ChannelMessageWithSource(InteractionCallbackData::new().content(content))
+    }
+
+    /// Replies with a plain text message only the invoking user can see.
+    pub fn ephemeral(content: impl Into<String>) -> Self {
+        InteractionResponse::ChannelMessageWithSource(
+            InteractionCallbackData::new()
+                .content(content)
+                .flags(MessageFlags::EPHEMERAL),
+        )
+    }
+
+    pub fn deferred() -> Self {
+        InteractionResponse::DeferredChannelMessageWithSource(None)
+    }
+
+    pub fn deferred_ephemeral() -> Self {
+        InteractionResponse::DeferredChannelMessageWithSource(Some(MessageFlags::EPHEMERAL))
+    }
+
+    pub fn callback_type(&self) -> u8 {
+        match self {
+            InteractionResponse::Pong => 1,
+            InteractionResponse::ChannelMessageWithSource(_) => 4,
+            InteractionResponse::DeferredChannelMessageWithSource(_) => 5,
+            InteractionResponse::DeferredUpdateMessage => 6,
+            InteractionResponse::UpdateMessage(_) => 7,
+            InteractionResponse::ApplicationCommandAutocompleteResult(_) => 8,
+            InteractionResponse::Modal(_) => 9,
+            InteractionResponse::PremiumRequired => 10,
+            InteractionResponse::LaunchActivity => 12,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DeferredData {
+    flags: MessageFlags,
+}
+
+#[derive(Serialize)]
+struct AutocompleteData<'a> {
+    choices: &'a [CommandOptionChoice],
+}
+
+impl Serialize for InteractionResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("InteractionResponse", 2)?;
+        state.serialize_field("type", &self.callback_type())?;
+        match self {
+            InteractionResponse::ChannelMessageWithSource(data)
+            | InteractionResponse::UpdateMessage(data) => state.serialize_field("data", data)?,
+            InteractionResponse::DeferredChannelMessageWithSource(Some(flags)) => {
+                state.serialize_field("data", &DeferredData { flags: *flags })?
+            }
+            InteractionResponse::ApplicationCommandAutocompleteResult(choices) => {
+                state.serialize_field("data", &AutocompleteData { choices })?
+            }
+            InteractionResponse::Modal(modal) => state.serialize_field("data", modal)?,
+            InteractionResponse::Pong
+            | InteractionResponse::DeferredChannelMessageWithSource(None)
+            | InteractionResponse::DeferredUpdateMessage
+            | InteractionResponse::PremiumRequired
+            | InteractionResponse::LaunchActivity => {}
+        }
+        state.end()
+    }
+}
+
+/// Message content for `CHANNEL_MESSAGE_WITH_SOURCE` and `UPDATE_MESSAGE` responses.
+///
+/// Built by chaining setters, e.g.
+/// `InteractionCallbackData::new().content("Hello").flags(MessageFlags::EPHEMERAL)`.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct InteractionCallbackData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tts: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<Embed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_mentions: Option<AllowedMentions>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flags: Option<MessageFlags>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub components: Option<Vec<Component>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<PartialAttachment>>,
+}
+
+impl InteractionCallbackData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tts(mut self, tts: bool) -> Self {
+        self.tts = Some(tts);
+        self
+    }
+
+    pub fn content(mut self, content: impl Into<String>) -> Self {
+        self.content = Some(content.into());
+        self
+    }
+
+    pub fn embed(mut self, embed: Embed) -> Self {
+        self.embeds.get_or_insert_with(Vec::new).push(embed);
+        self
+    }
+
+    pub fn embeds(mut self, embeds: Vec<Embed>) -> Self {
+        self.embeds = Some(embeds);
+        self
+    }
+
+    pub fn allowed_mentions(mut self, allowed_mentions: AllowedMentions) -> Self {
+        self.allowed_mentions = Some(allowed_mentions);
+        self
+    }
+
+    pub fn flags(mut self, flags: MessageFlags) -> Self {
+        self.flags = Some(flags);
+        self
+    }
+
+    pub fn component(mut self, component: Component) -> Self {
+        self.components.get_or_insert_with(Vec::new).push(component);
+        self
+    }
+
+    pub fn components(mut self, components: Vec<Component>) -> Self {
+        self.components = Some(components);
+        self
+    }
+
+    pub fn attachment(mut self, attachment: PartialAttachment) -> Self {
+        self.attachments.get_or_insert_with(Vec::new).push(attachment);
+        self
+    }
+
+    pub fn attachments(mut self, attachments: Vec<PartialAttachment>) -> Self {
+        self.attachments = Some(attachments);
+        self
+    }
+}
+
+/// Message flags combined as a bitfield.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(transparent)]
+pub struct MessageFlags(pub u64);
+
+impl MessageFlags {
+    pub const SUPPRESS_EMBEDS: MessageFlags = MessageFlags(1 << 2);
+    pub const EPHEMERAL: MessageFlags = MessageFlags(1 << 6);
+    pub const SUPPRESS_NOTIFICATIONS: MessageFlags = MessageFlags(1 << 12);
+    pub const IS_COMPONENTS_V2: MessageFlags = MessageFlags(1 << 15);
+
+    pub fn contains(self, other: MessageFlags) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+impl BitOr for MessageFlags {
+    type Output = MessageFlags;
+
+    fn bitor(self, rhs: MessageFlags) -> MessageFlags {
+        MessageFlags(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for MessageFlags {
+    fn bitor_assign(&mut self, rhs: MessageFlags) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Controls which mentions in a message actually ping.
+///
+/// The default allows no mentions at all. Discord rejects a `parse` type together with
+/// an explicit list of the same type.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct AllowedMentions {
+    pub parse: Vec<AllowedMentionType>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<Snowflake>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub users: Vec<Snowflake>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub replied_user: bool,
+}
+
+impl AllowedMentions {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn all() -> Self {
+        AllowedMentions {
+            parse: vec![
+                AllowedMentionType::Roles,
+                AllowedMentionType::Users,
+                AllowedMentionType::Everyone,
+            ],
+            replied_user: true,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum AllowedMentionType {
+    Roles,
+    Users,
+    Everyone,
+}
+
+/// An attachment reference in a response, pointing at an uploaded file by its `id`.
+#[derive(Serialize, Debug, Clone)]
+pub struct PartialAttachment {
+    pub id: Snowflake,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filename: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A suggested value for an autocomplete option.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CommandOptionChoice {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name_localizations: Option<HashMap<String, String>>,
+    pub value: CommandOptionChoiceValue,
+}
+
+impl CommandOptionChoice {
+    pub fn new(name: impl Into<String>, value: impl Into<CommandOptionChoiceValue>) -> Self {
+        CommandOptionChoice {
+            name: name.into(),
+            name_localizations: None,
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum CommandOptionChoiceValue {
+    String(String),
+    Integer(i64),
+    Number(f64),
+}
+
+impl From<String> for CommandOptionChoiceValue {
+    fn from(value: String) -> Self {
+        CommandOptionChoiceValue::String(value)
+    }
+}
+
+impl From<&str> for CommandOptionChoiceValue {
+    fn from(value: &str) -> Self {
+        CommandOptionChoiceValue::String(value.to_string())
+    }
+}
+
+impl From<i64> for CommandOptionChoiceValue {
+    fn from(value: i64) -> Self {
+        CommandOptionChoiceValue::Integer(value)
+    }
+}
+
+impl From<f64> for CommandOptionChoiceValue {
+    fn from(value: f64) -> Self {
+        CommandOptionChoiceValue::Number(value)
+    }
+}
+
+/// The popup shown by a `MODAL` response.
+#[derive(Serialize, Debug, Clone)]
+pub struct ModalData {
+    pub custom_id: String,
+    pub title: String,
+    pub components: Vec<Component>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        AllowedMentions, CommandOptionChoice, InteractionCallbackData, InteractionResponse,
+        MessageFlags,
+    };
+    use crate::model::message::Embed;
+    use serde_json::json;
+
+    #[test]
+    fn serialize_message_response() {
+        let response = InteractionResponse::ChannelMessageWithSource(
+            InteractionCallbackData::new()
+                .content("Hello")
+                .embed(Embed {
+                    title: Some("Title".to_string()),
+                    ..Embed::default()
+                })
+                .allowed_mentions(AllowedMentions::none())
+                .flags(MessageFlags::EPHEMERAL | MessageFlags::SUPPRESS_EMBEDS),
+        );
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "type": 4,
+                "data": {
+                    "content": "Hello",
+                    "embeds": [{"title": "Title"}],
+                    "allowed_mentions": {"parse": []},
+                    "flags": 68
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn serialize_responses_without_data() {
+        assert_eq!(serde_json::to_value(InteractionResponse::Pong).unwrap(), json!({"type": 1}));
+        assert_eq!(
+            serde_json::to_value(InteractionResponse::deferred()).unwrap(),
+            json!({"type": 5})
+        );
+        assert_eq!(
+            serde_json::to_value(InteractionResponse::deferred_ephemeral()).unwrap(),
+            json!({"type": 5, "data": {"flags": 64}})
+        );
+        assert_eq!(
+            serde_json::to_value(InteractionResponse::DeferredUpdateMessage).unwrap(),
+            json!({"type": 6})
+        );
+        assert_eq!(
+            serde_json::to_value(InteractionResponse::LaunchActivity).unwrap(),
+            json!({"type": 12})
+        );
+    }
+
+    #[test]
+    fn serialize_autocomplete_result() {
+        let response = InteractionResponse::ApplicationCommandAutocompleteResult(vec![
+            CommandOptionChoice::new("One", 1),
+            CommandOptionChoice::new("Two", "two"),
+        ]);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!({
+                "type": 8,
+                "data": {"choices": [{"name": "One", "value": 1}, {"name": "Two", "value": "two"}]}
+            })
+        );
+    }
+}
@@ -3,27 +3,27 @@ use crate::model::primitives::{Snowflake};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct User {
-    id: Snowflake,
-    username: String,
-    discriminator: String,
-    global_name: Option<String>,
-    avatar: Option<String>,
-    bot: Option<bool>,
-    system: Option<bool>,
-    mfa_enabled: Option<bool>,
-    banner: Option<String>,
-    accent_color: Option<i32>,
-    locale: Option<String>,
-    verified: Option<bool>,
-    email: Option<String>,
-    flags: Option<i32>,
-    premium_type: Option<i32>,
-    public_flags: Option<i32>,
-    avatar_decoration_data: Option<AvatarDecorationData>,
+    pub id: Snowflake,
+    pub username: String,
+    pub discriminator: String,
+    pub global_name: Option<String>,
+    pub avatar: Option<String>,
+    pub bot: Option<bool>,
+    pub system: Option<bool>,
+    pub mfa_enabled: Option<bool>,
+    pub banner: Option<String>,
+    pub accent_color: Option<i32>,
+    pub locale: Option<String>,
+    pub verified: Option<bool>,
+    pub email: Option<String>,
+    pub flags: Option<i32>,
+    pub premium_type: Option<i32>,
+    pub public_flags: Option<i32>,
+    pub avatar_decoration_data: Option<AvatarDecorationData>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct AvatarDecorationData {
-    sku_id: Snowflake,
-    assert: String,
+    pub sku_id: Snowflake,
+    pub asset: String,
 }
\ No newline at end of file
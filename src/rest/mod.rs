@@ -0,0 +1,355 @@
+use crate::model::channel::Channel;
+use crate::model::guild::{Guild, GuildMember};
+use crate::model::message::Message;
+use crate::model::primitives::Snowflake;
+use crate::model::response::InteractionCallbackData;
+use crate::model::user::User;
+use crate::rest::ratelimit::{RateLimitPolicy, RateLimiter, Route};
+use lambda_http::tracing;
+use reqwest::header::{AUTHORIZATION, USER_AGENT};
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Duration;
+
+pub mod ratelimit;
+pub mod webhook;
+
+/// The Discord API base URL, pinned to the API version the models follow.
+pub const DEFAULT_BASE_URL: &str = "https://discord.com/api/v10";
+
+const USER_AGENT_VALUE: &str = concat!("DiscordBot (serverless-discord, ", env!("CARGO_PKG_VERSION"), ")");
+
+/// The JSON error body Discord returns for failed requests.
+#[derive(Debug, Clone, Deserialize)]
+pub struct DiscordError {
+    /// Discord's JSON error code, `0` when absent.
+    #[serde(default)]
+    pub code: u32,
+    pub message: String,
+    /// Per-field validation errors, for `Invalid Form Body` errors.
+    pub errors: Option<Value>,
+}
+
+/// Why a REST request failed.
+#[derive(Debug)]
+pub enum RestError {
+    /// The request could not be sent or the response could not be read.
+    Http(reqwest::Error),
+    /// Discord rejected the request with a JSON error.
+    Api { status: StatusCode, error: DiscordError },
+    /// Discord rejected the request with a body that is not a JSON error.
+    Status { status: StatusCode, body: String },
+    /// The response body did not match the expected model.
+    Decode(serde_json::Error),
+    /// The request was not sent, or was rejected with a 429, because of a rate limit.
+    RateLimited { retry_after: Duration, global: bool },
+    /// The interaction token is older than 15 minutes, so its webhook can no longer be used.
+    InteractionTokenExpired,
+}
+
+impl Display for RestError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RestError::Http(e) => write!(f, "Request failed: {}", e),
+            RestError::Api { status, error } => write!(
+                f,
+                "Discord returned {}: {} (code {})",
+                status, error.message, error.code
+            ),
+            RestError::Status { status, body } => write!(f, "Discord returned {}: {}", status, body),
+            RestError::Decode(e) => write!(f, "Failed to decode response: {}", e),
+            RestError::RateLimited { retry_after, global } => write!(
+                f,
+                "Rate limited{} for {:.3}s",
+                if *global { " globally" } else { "" },
+                retry_after.as_secs_f64()
+            ),
+            RestError::InteractionTokenExpired => write!(f, "Interaction token has expired"),
+        }
+    }
+}
+
+impl std::error::Error for RestError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RestError::Http(e) => Some(e),
+            RestError::Decode(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for RestError {
+    fn from(e: reqwest::Error) -> Self {
+        RestError::Http(e)
+    }
+}
+
+/// An async client for the Discord REST API, authenticated with a bot token, or
+/// unauthenticated for endpoints authenticated by their URL, such as interaction webhooks.
+///
+/// Requests are rate limited per route, see [`RateLimitPolicy`]. Cloning is cheap and shares
+/// the connection pool and rate limits.
+#[derive(Clone)]
+pub struct RestClient {
+    http: reqwest::Client,
+    base_url: String,
+    bot_token: Option<String>,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+impl RestClient {
+    pub fn new(bot_token: &str) -> Self {
+        RestClient::unauthenticated().with_bot_token(bot_token)
+    }
+
+    /// Creates a client without a bot token, which can only call interaction webhooks.
+    pub fn unauthenticated() -> Self {
+        RestClient {
+            http: reqwest::Client::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            bot_token: None,
+            rate_limiter: Arc::new(RateLimiter::default()),
+        }
+    }
+
+    /// Authenticates requests with `bot_token`, keeping the base URL and rate limits.
+    pub fn with_bot_token(mut self, bot_token: &str) -> Self {
+        self.bot_token = Some(bot_token.to_string());
+        self
+    }
+
+    /// Sets whether to wait for rate limits to reset or to fail fast. Defaults to waiting.
+    pub fn with_rate_limit_policy(mut self, policy: RateLimitPolicy) -> Self {
+        self.rate_limiter = Arc::new(RateLimiter::new(policy));
+        self
+    }
+
+    /// Sends requests to `base_url` instead of Discord, e.g. a local mock server.
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    pub fn bot_token(&self) -> Option<&str> {
+        self.bot_token.as_deref()
+    }
+
+    pub async fn get_current_user(&self) -> Result<User, RestError> {
+        self.request(Method::GET, "/users/@me", None::<&()>).await
+    }
+
+    pub async fn get_user(&self, user_id: &Snowflake) -> Result<User, RestError> {
+        self.request(Method::GET, &format!("/users/{}", user_id), None::<&()>)
+            .await
+    }
+
+    pub async fn get_channel(&self, channel_id: &Snowflake) -> Result<Channel, RestError> {
+        self.request(Method::GET, &format!("/channels/{}", channel_id), None::<&()>)
+            .await
+    }
+
+    pub async fn get_guild(&self, guild_id: &Snowflake) -> Result<Guild, RestError> {
+        self.request(Method::GET, &format!("/guilds/{}", guild_id), None::<&()>)
+            .await
+    }
+
+    pub async fn get_guild_member(
+        &self,
+        guild_id: &Snowflake,
+        user_id: &Snowflake,
+    ) -> Result<GuildMember, RestError> {
+        let path = format!("/guilds/{}/members/{}", guild_id, user_id);
+        self.request(Method::GET, &path, None::<&()>).await
+    }
+
+    pub async fn get_message(
+        &self,
+        channel_id: &Snowflake,
+        message_id: &Snowflake,
+    ) -> Result<Message, RestError> {
+        let path = format!("/channels/{}/messages/{}", channel_id, message_id);
+        self.request(Method::GET, &path, None::<&()>).await
+    }
+
+    /// Posts a message to a channel. The message fields match those of an interaction reply.
+    pub async fn create_message(
+        &self,
+        channel_id: &Snowflake,
+        message: &InteractionCallbackData,
+    ) -> Result<Message, RestError> {
+        let path = format!("/channels/{}/messages", channel_id);
+        self.request(Method::POST, &path, Some(message)).await
+    }
+
+    pub async fn edit_message(
+        &self,
+        channel_id: &Snowflake,
+        message_id: &Snowflake,
+        message: &InteractionCallbackData,
+    ) -> Result<Message, RestError> {
+        let path = format!("/channels/{}/messages/{}", channel_id, message_id);
+        self.request(Method::PATCH, &path, Some(message)).await
+    }
+
+    pub async fn delete_message(
+        &self,
+        channel_id: &Snowflake,
+        message_id: &Snowflake,
+    ) -> Result<(), RestError> {
+        let path = format!("/channels/{}/messages/{}", channel_id, message_id);
+        self.send(Method::DELETE, &path, None::<&()>).await.map(|_| ())
+    }
+
+    pub(crate) async fn request<T, B>(&self, method: Method, path: &str, body: Option<&B>) -> Result<T, RestError>
+    where
+        T: DeserializeOwned,
+        B: Serialize + ?Sized,
+    {
+        let bytes = self.send(method, path, body).await?;
+        serde_json::from_slice(&bytes).map_err(RestError::Decode)
+    }
+
+    /// Sends a request and returns the body of a successful response, waiting for and
+    /// retrying on rate limits as the policy allows.
+    pub(crate) async fn send<B>(&self, method: Method, path: &str, body: Option<&B>) -> Result<Vec<u8>, RestError>
+    where
+        B: Serialize + ?Sized,
+    {
+        let route = Route::new(&method, path);
+        let mut retries = 0;
+
+        loop {
+            self.rate_limiter.acquire(&route).await?;
+
+            let mut request = self
+                .http
+                .request(method.clone(), format!("{}{}", self.base_url, path))
+                .header(USER_AGENT, USER_AGENT_VALUE);
+            if let Some(bot_token) = &self.bot_token {
+                request = request.header(AUTHORIZATION, format!("Bot {}", bot_token));
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let response = request.send().await?;
+            let status = response.status();
+            let headers = response.headers().clone();
+            let bytes = response.bytes().await?;
+
+            if let Some((retry_after, global)) = self.rate_limiter.update(&route, status, &headers, &bytes) {
+                match self.rate_limiter.policy() {
+                    RateLimitPolicy::Wait { max_retries } if retries < max_retries => {
+                        tracing::warn!("Rate limited on {}, retrying in {:?}", path, retry_after);
+                        retries += 1;
+                        continue;
+                    }
+                    _ => return Err(RestError::RateLimited { retry_after, global }),
+                }
+            }
+
+            if status.is_success() {
+                return Ok(bytes.to_vec());
+            }
+
+            return Err(match serde_json::from_slice::<DiscordError>(&bytes) {
+                Ok(error) => RestError::Api { status, error },
+                Err(_) => RestError::Status {
+                    status,
+                    body: String::from_utf8_lossy(&bytes).into_owned(),
+                },
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{RestClient, RestError};
+    use crate::model::primitives::Snowflake;
+    use crate::model::response::InteractionCallbackData;
+    use axum::extract::Path;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+
+    const USER: &str = r#"{"id":"1096551423958855810","username":"bot","discriminator":"0","avatar":null,"bot":true}"#;
+
+    /// A message as Discord returns it, sent by the bot.
+    pub(crate) fn message(id: &str, content: &str) -> Value {
+        json!({"id":id,"channel_id":"4","author":serde_json::from_str::<Value>(USER).unwrap(),"content":content,"timestamp":"2025-05-13T00:00:00.000000+00:00","edited_timestamp":null,"tts":false,"mention_everyone":false,"mentions":[],"mention_roles":[],"attachments":[],"embeds":[],"pinned":false,"type":0})
+    }
+
+    /// Serves `router` on a local port and returns its base URL.
+    pub(crate) async fn mock_server(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+        format!("http://{}", address)
+    }
+
+    /// Serves `router` on a local port and returns a client authenticated as `token`.
+    pub(crate) async fn mock_client(router: Router) -> RestClient {
+        RestClient::unauthenticated()
+            .with_base_url(&mock_server(router).await)
+            .with_bot_token("token")
+    }
+
+    #[tokio::test]
+    async fn authenticate_and_decode_models() {
+        let router = Router::new()
+            .route(
+                "/users/@me",
+                get(|headers: HeaderMap| async move {
+                    assert_eq!(headers["authorization"], "Bot token");
+                    assert!(headers["user-agent"].to_str().unwrap().starts_with("DiscordBot ("));
+                    USER
+                }),
+            )
+            .route(
+                "/channels/{channel}/messages",
+                post(|Json(body): Json<Value>| async move {
+                    Json(message("3", body["content"].as_str().unwrap()))
+                }),
+            );
+        let client = mock_client(router).await;
+
+        let user = client.get_current_user().await.unwrap();
+        assert_eq!(user.username, "bot");
+
+        let data = InteractionCallbackData::new().content("Hello");
+        let message = client.create_message(&Snowflake::from(4), &data).await.unwrap();
+        assert_eq!(message.content, "Hello");
+    }
+
+    #[tokio::test]
+    async fn decode_json_errors() {
+        let router = Router::new().route(
+            "/channels/{channel}/messages/{message}",
+            get(|Path((_, _)): Path<(String, String)>| async {
+                (
+                    StatusCode::NOT_FOUND,
+                    r#"{"message":"Unknown Message","code":10008}"#,
+                )
+            }),
+        );
+        let client = mock_client(router).await;
+
+        let result = client.get_message(&Snowflake::from(4), &Snowflake::from(3)).await;
+
+        match result {
+            Err(RestError::Api { status, error }) => {
+                assert_eq!(status, StatusCode::NOT_FOUND);
+                assert_eq!(error.code, 10008);
+                assert_eq!(error.message, "Unknown Message");
+            }
+            other => panic!("Expected an API error, got {:?}", other),
+        }
+    }
+}
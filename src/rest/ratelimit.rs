@@ -0,0 +1,349 @@
+use crate::rest::RestError;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Path segments whose ID is a major parameter, rate limited separately per ID.
+const MAJOR_PARAMETERS: [&str; 3] = ["channels", "guilds", "webhooks"];
+
+/// What to do when a request would exceed a rate limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitPolicy {
+    /// Sleep until the limit resets, and retry requests rejected with a 429 up to
+    /// `max_retries` times.
+    Wait { max_retries: u32 },
+    /// Return [`RestError::RateLimited`] immediately.
+    FailFast,
+}
+
+impl Default for RateLimitPolicy {
+    fn default() -> Self {
+        RateLimitPolicy::Wait { max_retries: 3 }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    remaining: u32,
+    reset_at: Instant,
+}
+
+#[derive(Default)]
+struct State {
+    /// The bucket hash Discord reported for each route.
+    routes: HashMap<String, String>,
+    /// Bucket state by bucket hash and major parameter, or by route until the hash is known.
+    buckets: HashMap<String, Bucket>,
+    global_reset_at: Option<Instant>,
+}
+
+impl State {
+    fn bucket_key(&self, route: &Route) -> String {
+        match self.routes.get(&route.key) {
+            Some(hash) => format!("{}:{}", hash, route.major),
+            None => format!("{}:{}", route.key, route.major),
+        }
+    }
+}
+
+/// The route a request counts against, identified by its method, path template and major
+/// parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Route {
+    key: String,
+    major: String,
+}
+
+impl Route {
+    /// Replaces every ID and interaction token in `path` except the major parameter, so
+    /// `/channels/1/messages/2` becomes `/channels/1/messages/:id`.
+    ///
+    /// A webhook's major parameter is its ID together with its token, so each interaction
+    /// gets its own buckets. Only a hash of the token is kept.
+    pub(crate) fn new(method: &Method, path: &str) -> Self {
+        let segments: Vec<&str> = path.split('/').collect();
+        let is_major = segments.get(1).is_some_and(|resource| MAJOR_PARAMETERS.contains(resource));
+        let mut major = match segments.get(2) {
+            Some(id) if is_major => id.to_string(),
+            _ => String::new(),
+        };
+        if let Some(token) = segments.get(3).filter(|_| segments[1] == "webhooks") {
+            let mut hasher = DefaultHasher::new();
+            token.hash(&mut hasher);
+            major = format!("{}:{:016x}", major, hasher.finish());
+        }
+
+        let template: Vec<&str> = segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| match index {
+                2 if is_major => segment,
+                3 if matches!(segments[1], "webhooks" | "interactions") => ":token",
+                _ if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) => ":id",
+                _ => segment,
+            })
+            .collect();
+
+        Route {
+            key: format!("{} {}", method, template.join("/")),
+            major,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RateLimitBody {
+    retry_after: Option<f64>,
+    #[serde(default)]
+    global: bool,
+}
+
+/// Tracks Discord's per-route buckets and global limit from the `X-RateLimit-*` headers.
+#[derive(Default)]
+pub struct RateLimiter {
+    policy: RateLimitPolicy,
+    state: Mutex<State>,
+}
+
+impl RateLimiter {
+    pub fn new(policy: RateLimitPolicy) -> Self {
+        RateLimiter {
+            policy,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    pub fn policy(&self) -> RateLimitPolicy {
+        self.policy
+    }
+
+    /// Waits until a request on `route` is allowed, or fails fast depending on the policy.
+    pub(crate) async fn acquire(&self, route: &Route) -> Result<(), RestError> {
+        loop {
+            let (wait, global) = self.reserve(route);
+            let Some(wait) = wait else {
+                return Ok(());
+            };
+
+            match self.policy {
+                RateLimitPolicy::FailFast => {
+                    return Err(RestError::RateLimited {
+                        retry_after: wait,
+                        global,
+                    })
+                }
+                RateLimitPolicy::Wait { .. } => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+
+    /// Takes a request from the route's bucket, or returns how long to wait for it to reset.
+    fn reserve(&self, route: &Route) -> (Option<Duration>, bool) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+
+        if let Some(reset_at) = state.global_reset_at {
+            if reset_at > now {
+                return (Some(reset_at - now), true);
+            }
+            state.global_reset_at = None;
+        }
+
+        let key = state.bucket_key(route);
+        match state.buckets.get_mut(&key) {
+            Some(bucket) if bucket.reset_at <= now => {
+                state.buckets.remove(&key);
+                (None, false)
+            }
+            Some(bucket) if bucket.remaining == 0 => (Some(bucket.reset_at - now), false),
+            Some(bucket) => {
+                bucket.remaining -= 1;
+                (None, false)
+            }
+            None => (None, false),
+        }
+    }
+
+    /// Records the limits reported by a response, returning how long to wait before
+    /// retrying if it was a 429.
+    pub(crate) fn update(
+        &self,
+        route: &Route,
+        status: StatusCode,
+        headers: &HeaderMap,
+        body: &[u8],
+    ) -> Option<(Duration, bool)> {
+        let now = Instant::now();
+        let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok());
+        let seconds = |name: &str| header(name).and_then(|value| value.parse::<f64>().ok());
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        // Webhook buckets are keyed by single use interaction tokens, so drop every bucket
+        // that has reset rather than waiting for its key to come up again.
+        state.buckets.retain(|_, bucket| bucket.reset_at > now);
+        if let Some(hash) = header("x-ratelimit-bucket") {
+            state.routes.insert(route.key.clone(), hash.to_string());
+        }
+
+        let key = state.bucket_key(route);
+        if let (Some(remaining), Some(reset_after)) = (
+            header("x-ratelimit-remaining").and_then(|value| value.parse().ok()),
+            seconds("x-ratelimit-reset-after"),
+        ) {
+            let reset_at = now + Duration::from_secs_f64(reset_after.max(0.0));
+            state.buckets.insert(key.clone(), Bucket { remaining, reset_at });
+        }
+
+        if status != StatusCode::TOO_MANY_REQUESTS {
+            return None;
+        }
+
+        let body = serde_json::from_slice::<RateLimitBody>(body).ok();
+        let retry_after = body
+            .as_ref()
+            .and_then(|body| body.retry_after)
+            .or_else(|| seconds("retry-after"))
+            .unwrap_or(1.0);
+        let retry_after = Duration::from_secs_f64(retry_after.max(0.0));
+        let global = header("x-ratelimit-global").is_some() || body.is_some_and(|body| body.global);
+
+        if global {
+            state.global_reset_at = Some(now + retry_after);
+        } else {
+            state.buckets.insert(
+                key,
+                Bucket {
+                    remaining: 0,
+                    reset_at: now + retry_after,
+                },
+            );
+        }
+        Some((retry_after, global))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{RateLimitPolicy, RateLimiter, Route, State};
+    use crate::model::primitives::Snowflake;
+    use crate::rest::tests::mock_client;
+    use crate::rest::RestError;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::get;
+    use axum::Router;
+    use reqwest::Method;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const USER: &str = r#"{"id":"1096551423958855810","username":"bot","discriminator":"0","avatar":null}"#;
+
+    #[test]
+    fn template_routes_by_major_parameter() {
+        let route = Route::new(&Method::GET, "/channels/4/messages/3");
+        assert_eq!(route.key, "GET /channels/4/messages/:id");
+        assert_eq!(route.major, "4");
+
+        let route = Route::new(&Method::PATCH, "/webhooks/1/secret-token/messages/@original");
+        assert_eq!(route.key, "PATCH /webhooks/1/:token/messages/@original");
+        assert!(route.major.starts_with("1:"));
+        assert!(!route.major.contains("secret-token"));
+
+        let other = Route::new(&Method::PATCH, "/webhooks/1/other-token/messages/@original");
+        assert_eq!(other.key, route.key);
+        assert_ne!(other.major, route.major);
+        let mut state = State::default();
+        assert_ne!(state.bucket_key(&other), state.bucket_key(&route));
+        state.routes.insert(route.key.clone(), "abcd".to_string());
+        assert_ne!(state.bucket_key(&other), state.bucket_key(&route));
+
+        let route = Route::new(&Method::POST, "/interactions/2/secret-token/callback");
+        assert_eq!(route.key, "POST /interactions/:id/:token/callback");
+
+        let route = Route::new(&Method::GET, "/users/5");
+        assert_eq!(route.key, "GET /users/:id");
+        assert_eq!(route.major, "");
+    }
+
+    #[test]
+    fn forget_buckets_once_they_reset() {
+        let limiter = RateLimiter::default();
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-bucket", "abcd".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "4".parse().unwrap());
+        headers.insert("x-ratelimit-reset-after", "0".parse().unwrap());
+
+        for token in 0..10 {
+            let path = format!("/webhooks/1/token-{}/messages/@original", token);
+            limiter.update(&Route::new(&Method::PATCH, &path), StatusCode::OK, &headers, b"");
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        let state = limiter.state.lock().unwrap();
+        assert_eq!(state.buckets.len(), 1);
+        assert_eq!(state.routes.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fail_fast_on_exhausted_bucket() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let router = Router::new().route(
+            "/users/{id}",
+            get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut headers = HeaderMap::new();
+                headers.insert("x-ratelimit-bucket", "abcd".parse().unwrap());
+                headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+                headers.insert("x-ratelimit-reset-after", "60".parse().unwrap());
+                async move { (headers, USER) }
+            }),
+        );
+        let client = mock_client(router)
+            .await
+            .with_rate_limit_policy(RateLimitPolicy::FailFast);
+
+        assert!(client.get_user(&Snowflake::from(5)).await.is_ok());
+        match client.get_user(&Snowflake::from(6)).await {
+            Err(RestError::RateLimited { retry_after, global }) => {
+                assert!(retry_after.as_secs() > 50);
+                assert!(!global);
+            }
+            other => panic!("Expected a rate limit, got {:?}", other),
+        }
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn retry_after_429() {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let router = Router::new().route(
+            "/users/@me",
+            get(move || {
+                let first = counter.fetch_add(1, Ordering::SeqCst) == 0;
+                async move {
+                    if first {
+                        let mut headers = HeaderMap::new();
+                        headers.insert("retry-after", "1".parse().unwrap());
+                        headers.insert("x-ratelimit-global", "true".parse().unwrap());
+                        let body = r#"{"message":"You are being rate limited.","retry_after":0.05,"global":true}"#;
+                        (StatusCode::TOO_MANY_REQUESTS, headers, body)
+                    } else {
+                        (StatusCode::OK, HeaderMap::new(), USER)
+                    }
+                }
+            }),
+        );
+        let client = mock_client(router).await;
+
+        let user = client.get_current_user().await.unwrap();
+        assert_eq!(user.username, "bot");
+        assert_eq!(hits.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,311 @@
+use crate::model::interactions::InteractionBase;
+use crate::model::message::Message;
+use crate::model::primitives::Snowflake;
+use crate::model::response::{InteractionCallbackData, InteractionResponse};
+use crate::rest::{RestClient, RestError};
+use reqwest::Method;
+use std::time::{Duration, SystemTime};
+
+/// How long an interaction token can be used after the interaction was created.
+pub const INTERACTION_TOKEN_LIFETIME: Duration = Duration::from_secs(15 * 60);
+
+/// Manages the response to an interaction and its follow-up messages through the
+/// interaction's webhook, `/webhooks/{application_id}/{token}`.
+///
+/// The token expires 15 minutes after the interaction was created, after which every
+/// method fails with [`RestError::InteractionTokenExpired`] without calling Discord.
+///
+/// Handlers that need longer than Discord's 3 second window acknowledge the interaction
+/// through [`create_response`](Self::create_response) first, then do the work and edit the
+/// original response before returning:
+///
+/// ```no_run
+/// # use serverless_discord::model::response::{InteractionCallbackData, InteractionResponse};
+/// # use serverless_discord::router::command::CommandContext;
+/// # async fn build_report() -> String { String::new() }
+/// async fn report(ctx: CommandContext) -> InteractionResponse {
+///     let deferred = InteractionResponse::deferred();
+///     if let Err(e) = ctx.webhook.create_response(&deferred).await {
+///         return InteractionResponse::ephemeral(format!("Could not start the report: {}", e));
+///     }
+///
+///     let summary = InteractionCallbackData::new().content(build_report().await);
+///     ctx.webhook.edit_original(&summary).await.ok();
+///     deferred
+/// }
+/// ```
+///
+/// The work finishes before the handler returns, which matters on AWS Lambda: the execution
+/// environment can be frozen as soon as the response has been returned, pausing any task
+/// spawned to run on afterwards until the next invocation arrives. The returned deferral
+/// only repeats the acknowledgement already sent through the callback.
+/// [`App::with_deferral_watchdog`](crate::App::with_deferral_watchdog) does the same for
+/// handlers that simply return their message late.
+#[derive(Clone)]
+pub struct InteractionWebhook {
+    client: RestClient,
+    interaction_id: Snowflake,
+    application_id: Snowflake,
+    token: String,
+    expires_at: SystemTime,
+}
+
+impl InteractionWebhook {
+    /// Creates a handle for a token issued now, expiring after [`INTERACTION_TOKEN_LIFETIME`].
+    pub fn new(
+        client: RestClient,
+        interaction_id: Snowflake,
+        application_id: Snowflake,
+        token: &str,
+    ) -> Self {
+        InteractionWebhook {
+            client,
+            interaction_id,
+            application_id,
+            token: token.to_string(),
+            expires_at: SystemTime::now() + INTERACTION_TOKEN_LIFETIME,
+        }
+    }
+
+    /// The webhook of the interaction described by `base`, expiring 15 minutes after the
+    /// creation time encoded in the interaction ID.
+    pub fn for_interaction(client: &RestClient, base: &InteractionBase) -> Self {
+        let webhook = InteractionWebhook::new(
+            client.clone(),
+            base.id.clone(),
+            base.application_id.clone(),
+            &base.token,
+        );
+        match base.id.timestamp() {
+            Some(created_at) => webhook.with_expires_at(created_at + INTERACTION_TOKEN_LIFETIME),
+            None => webhook,
+        }
+    }
+
+    pub fn with_expires_at(mut self, expires_at: SystemTime) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn interaction_id(&self) -> &Snowflake {
+        &self.interaction_id
+    }
+
+    pub fn application_id(&self) -> &Snowflake {
+        &self.application_id
+    }
+
+    pub fn expires_at(&self) -> SystemTime {
+        self.expires_at
+    }
+
+    pub fn is_expired(&self) -> bool {
+        SystemTime::now() >= self.expires_at
+    }
+
+    /// Sends the initial response through the interaction callback endpoint,
+    /// `/interactions/{id}/{token}/callback`, instead of returning it from the handler.
+    ///
+    /// Discord only accepts one initial response, within 3 seconds of the interaction.
+    pub async fn create_response(&self, response: &InteractionResponse) -> Result<(), RestError> {
+        if self.is_expired() {
+            return Err(RestError::InteractionTokenExpired);
+        }
+        let path = format!("/interactions/{}/{}/callback", self.interaction_id, self.token);
+        self.client.send(Method::POST, &path, Some(response)).await.map(|_| ())
+    }
+
+    /// Fetches the initial response, or the message a deferral was resolved with.
+    pub async fn fetch_original(&self) -> Result<Message, RestError> {
+        self.request(Method::GET, "/messages/@original", None).await
+    }
+
+    /// Replaces the deferred loading state, or the initial response, with `message`.
+    pub async fn edit_original(&self, message: &InteractionCallbackData) -> Result<Message, RestError> {
+        self.request(Method::PATCH, "/messages/@original", Some(message)).await
+    }
+
+    pub async fn delete_original(&self) -> Result<(), RestError> {
+        self.delete("/messages/@original").await
+    }
+
+    /// Sends a new message in reply to the interaction.
+    pub async fn create_followup(&self, message: &InteractionCallbackData) -> Result<Message, RestError> {
+        self.request(Method::POST, "", Some(message)).await
+    }
+
+    pub async fn fetch_followup(&self, message_id: &Snowflake) -> Result<Message, RestError> {
+        let path = format!("/messages/{}", message_id);
+        self.request(Method::GET, &path, None).await
+    }
+
+    pub async fn edit_followup(
+        &self,
+        message_id: &Snowflake,
+        message: &InteractionCallbackData,
+    ) -> Result<Message, RestError> {
+        let path = format!("/messages/{}", message_id);
+        self.request(Method::PATCH, &path, Some(message)).await
+    }
+
+    pub async fn delete_followup(&self, message_id: &Snowflake) -> Result<(), RestError> {
+        self.delete(&format!("/messages/{}", message_id)).await
+    }
+
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        message: Option<&InteractionCallbackData>,
+    ) -> Result<Message, RestError> {
+        let path = self.path(path)?;
+        self.client.request(method, &path, message).await
+    }
+
+    async fn delete(&self, path: &str) -> Result<(), RestError> {
+        let path = self.path(path)?;
+        self.client.send(Method::DELETE, &path, None::<&()>).await.map(|_| ())
+    }
+
+    /// The webhook path followed by `suffix`, failing once the token has expired.
+    fn path(&self, suffix: &str) -> Result<String, RestError> {
+        if self.is_expired() {
+            return Err(RestError::InteractionTokenExpired);
+        }
+        Ok(format!("/webhooks/{}/{}{}", self.application_id, self.token, suffix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::InteractionWebhook;
+    use crate::model::interactions::{Interaction, TestInteraction};
+    use crate::model::primitives::Snowflake;
+    use crate::model::response::{InteractionCallbackData, InteractionResponse};
+    use crate::rest::tests::{message, mock_server};
+    use crate::rest::RestClient;
+    use axum::extract::Path;
+    use axum::http::HeaderMap;
+    use crate::rest::RestError;
+    use axum::http::StatusCode;
+    use axum::routing::{get, patch, post};
+    use std::time::{Duration, SystemTime};
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+
+    #[tokio::test]
+    async fn edit_original_and_follow_up() {
+        let router = Router::new()
+            .route(
+                "/webhooks/{application}/{token}/messages/@original",
+                patch(
+                    |Path((application, token)): Path<(String, String)>,
+                     headers: HeaderMap,
+                     Json(body): Json<Value>| async move {
+                        assert_eq!((application.as_str(), token.as_str()), ("1", "example_token"));
+                        assert!(!headers.contains_key("authorization"));
+                        Json(message("3", body["content"].as_str().unwrap()))
+                    },
+                ),
+            )
+            .route(
+                "/webhooks/{application}/{token}",
+                post(|Json(body): Json<Value>| async move {
+                    Json(message("5", body["content"].as_str().unwrap()))
+                }),
+            );
+        let client = RestClient::unauthenticated().with_base_url(&mock_server(router).await);
+        let webhook = InteractionWebhook::new(client, Snowflake::from(2), Snowflake::from(1), "example_token");
+
+        let original = webhook
+            .edit_original(&InteractionCallbackData::new().content("Done"))
+            .await
+            .unwrap();
+        assert_eq!(original.content, "Done");
+
+        let followup = webhook
+            .create_followup(&InteractionCallbackData::new().content("One more thing"))
+            .await
+            .unwrap();
+        assert_eq!(followup.content, "One more thing");
+    }
+
+    #[tokio::test]
+    async fn acknowledge_through_callback() {
+        let router = Router::new().route(
+            "/interactions/{id}/{token}/callback",
+            post(
+                |Path((id, token)): Path<(String, String)>, Json(body): Json<Value>| async move {
+                    assert_eq!((id.as_str(), token.as_str()), ("2", "example_token"));
+                    assert_eq!(body, json!({"type": 5}));
+                    StatusCode::NO_CONTENT
+                },
+            ),
+        );
+        let client = RestClient::unauthenticated().with_base_url(&mock_server(router).await);
+        let webhook = InteractionWebhook::new(client, Snowflake::from(2), Snowflake::from(1), "example_token");
+
+        webhook.create_response(&InteractionResponse::deferred()).await.unwrap();
+
+        let expired = webhook.with_expires_at(SystemTime::now() - Duration::from_secs(1));
+        let result = expired.create_response(&InteractionResponse::deferred()).await;
+        assert!(matches!(result, Err(RestError::InteractionTokenExpired)));
+    }
+
+    #[tokio::test]
+    async fn manage_followups() {
+        let router = Router::new().route(
+            "/webhooks/{application}/{token}/messages/{message}",
+            get(|Path((_, _, id)): Path<(String, String, String)>| async move {
+                Json(message(&id, "Fetched"))
+            })
+            .patch(
+                |Path((_, _, id)): Path<(String, String, String)>, Json(body): Json<Value>| async move {
+                    Json(message(&id, body["content"].as_str().unwrap()))
+                },
+            )
+            .delete(|| async { StatusCode::NO_CONTENT }),
+        );
+        let client = RestClient::unauthenticated().with_base_url(&mock_server(router).await);
+        let webhook = InteractionWebhook::new(client, Snowflake::from(2), Snowflake::from(1), "example_token");
+
+        let original = webhook.fetch_original().await.unwrap();
+        assert_eq!(original.id, "@original");
+
+        let edited = webhook
+            .edit_followup(&Snowflake::from(5), &InteractionCallbackData::new().content("Edited"))
+            .await
+            .unwrap();
+        assert_eq!(edited.content, "Edited");
+
+        let fetched = webhook.fetch_followup(&Snowflake::from(5)).await.unwrap();
+        assert_eq!(fetched.id, "5");
+
+        webhook.delete_followup(&Snowflake::from(5)).await.unwrap();
+        webhook.delete_original().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn reject_expired_tokens() {
+        let client = RestClient::unauthenticated().with_base_url("http://127.0.0.1:9");
+        let webhook = InteractionWebhook::new(client, Snowflake::from(2), Snowflake::from(1), "example_token")
+            .with_expires_at(SystemTime::now() - Duration::from_secs(1));
+
+        assert!(webhook.is_expired());
+        let result = webhook.edit_original(&InteractionCallbackData::new().content("Late")).await;
+        assert!(matches!(result, Err(RestError::InteractionTokenExpired)));
+
+        let command = TestInteraction::new(2, json!({"id": "1", "name": "ping", "type": 1}))
+            .id(1371622186951114924)
+            .build();
+        let Interaction::ApplicationCommand(command) = command else {
+            panic!("Expected an application command");
+        };
+        let webhook = InteractionWebhook::for_interaction(&RestClient::unauthenticated(), &command.base);
+        assert_eq!(
+            webhook.expires_at(),
+            command.base.id.timestamp().unwrap() + Duration::from_secs(15 * 60)
+        );
+        assert!(webhook.is_expired());
+    }
+}
@@ -0,0 +1,210 @@
+use crate::model::application_command::{CommandOptionType, CommandOptionValue};
+use crate::model::interactions::CommandInteraction;
+use crate::model::response::{CommandOptionChoice, CommandOptionChoiceValue, InteractionResponse};
+use crate::router::command::resolve_path;
+use crate::router::options::CommandOptions;
+use crate::router::BoxFuture;
+use lambda_http::tracing;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// The maximum number of choices Discord accepts in an autocomplete result.
+pub const MAX_CHOICES: usize = 25;
+/// The maximum length, in characters, of a choice's name and of a string choice's value.
+pub const MAX_CHOICE_LENGTH: usize = 100;
+
+/// The option the user is currently typing in.
+#[derive(Debug, Clone)]
+pub struct FocusedOption {
+    pub name: String,
+    /// The partial input typed so far.
+    pub value: String,
+    pub kind: CommandOptionType,
+}
+
+/// Everything an autocomplete provider receives about the invocation.
+pub struct AutocompleteContext {
+    pub interaction: CommandInteraction,
+    pub focused: FocusedOption,
+    /// The other options the user has already filled in.
+    pub options: CommandOptions,
+}
+
+/// An async provider of autocomplete choices for a single command option.
+pub trait AutocompleteHandler: Send + Sync {
+    fn call(&self, ctx: AutocompleteContext) -> BoxFuture<Vec<CommandOptionChoice>>;
+}
+
+impl<F, Fut> AutocompleteHandler for F
+where
+    F: Fn(AutocompleteContext) -> Fut + Send + Sync,
+    Fut: Future<Output = Vec<CommandOptionChoice>> + Send + 'static,
+{
+    fn call(&self, ctx: AutocompleteContext) -> BoxFuture<Vec<CommandOptionChoice>> {
+        Box::pin(self(ctx))
+    }
+}
+
+/// Routes autocomplete interactions to providers registered by command path and option name.
+#[derive(Default)]
+pub struct AutocompleteRouter {
+    handlers: HashMap<(String, String), Box<dyn AutocompleteHandler>>,
+}
+
+impl AutocompleteRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a provider for an option of a slash command.
+    ///
+    /// `command` is the space separated path used by [`CommandRouter::chat_input`],
+    /// e.g. `"config roles add"`.
+    ///
+    /// [`CommandRouter::chat_input`]: crate::router::command::CommandRouter::chat_input
+    pub fn register<H>(mut self, command: &str, option: &str, handler: H) -> Self
+    where
+        H: AutocompleteHandler + 'static,
+    {
+        self.handlers
+            .insert((command.to_string(), option.to_string()), Box::new(handler));
+        self
+    }
+
+    /// Runs the provider registered for the focused option and wraps its choices in an
+    /// `APPLICATION_COMMAND_AUTOCOMPLETE_RESULT` response.
+    ///
+    /// Returns `None` if there is no focused option or no provider registered for it.
+    pub async fn dispatch(&self, interaction: CommandInteraction) -> Option<InteractionResponse> {
+        let (path, options) = resolve_path(&interaction.data);
+
+        let mut focused = None;
+        let mut filled = Vec::with_capacity(options.len());
+        for option in options {
+            match &option.value {
+                CommandOptionValue::Focused(value, kind) => {
+                    focused = Some(FocusedOption {
+                        name: option.name.clone(),
+                        value: value.clone(),
+                        kind: *kind,
+                    })
+                }
+                _ => filled.push(option.clone()),
+            }
+        }
+
+        let Some(focused) = focused else {
+            tracing::warn!("Autocomplete interaction for {} has no focused option", path);
+            return None;
+        };
+
+        let key = (path, focused.name.clone());
+        let Some(handler) = self.handlers.get(&key) else {
+            tracing::warn!("No autocomplete handler registered for {} {}", key.0, key.1);
+            return None;
+        };
+
+        let options = CommandOptions::new(filled, interaction.data.resolved.clone());
+        let choices = handler
+            .call(AutocompleteContext {
+                interaction,
+                focused,
+                options,
+            })
+            .await;
+
+        Some(InteractionResponse::ApplicationCommandAutocompleteResult(
+            enforce_limits(choices),
+        ))
+    }
+}
+
+/// Drops choices Discord would reject and truncates the list to [`MAX_CHOICES`].
+///
+/// Discord fails the whole autocomplete response if any choice is invalid, so invalid
+/// choices are logged and skipped instead.
+pub fn enforce_limits(choices: Vec<CommandOptionChoice>) -> Vec<CommandOptionChoice> {
+    let mut valid: Vec<_> = choices
+        .into_iter()
+        .filter(|choice| {
+            let name_length = choice.name.chars().count();
+            let value_valid = match &choice.value {
+                CommandOptionChoiceValue::String(value) => {
+                    value.chars().count() <= MAX_CHOICE_LENGTH
+                }
+                _ => true,
+            };
+            let valid = (1..=MAX_CHOICE_LENGTH).contains(&name_length) && value_valid;
+            if !valid {
+                tracing::warn!("Dropping autocomplete choice exceeding Discord's limits: {:?}", choice);
+            }
+            valid
+        })
+        .collect();
+
+    if valid.len() > MAX_CHOICES {
+        tracing::warn!(
+            "Truncating {} autocomplete choices to {}",
+            valid.len(),
+            MAX_CHOICES
+        );
+        valid.truncate(MAX_CHOICES);
+    }
+
+    valid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AutocompleteContext, AutocompleteRouter, MAX_CHOICES};
+    use crate::model::interactions::{CommandInteraction, Interaction, TestInteraction};
+    use serde_json::{json, Value};
+    use crate::model::response::{CommandOptionChoice, InteractionResponse};
+
+    fn autocomplete(options: &str) -> CommandInteraction {
+        let options: Value = serde_json::from_str(options).unwrap();
+        let data = json!({
+            "id": "1",
+            "name": "tag",
+            "type": 1,
+            "options": [{"name": "show", "type": 1, "options": options}]
+        });
+        match TestInteraction::new(4, data).build() {
+            Interaction::Autocomplete(command) => command,
+            other => panic!("Expected an autocomplete interaction, got {:?}", other),
+        }
+    }
+
+    async fn tags(ctx: AutocompleteContext) -> Vec<CommandOptionChoice> {
+        let prefix = ctx.options.get::<String>("prefix").unwrap();
+        let mut choices: Vec<_> = (0..40)
+            .map(|i| CommandOptionChoice::new(format!("{}{}{}", prefix, ctx.focused.value, i), i as i64))
+            .collect();
+        choices.insert(0, CommandOptionChoice::new("x".repeat(101), "too long"));
+        choices
+    }
+
+    #[tokio::test]
+    async fn dispatch_focused_option_and_enforce_limits() {
+        let router = AutocompleteRouter::new().register("tag show", "name", tags);
+
+        let interaction = autocomplete(
+            r#"[{"name":"prefix","type":3,"value":"!"},{"name":"name","type":3,"value":"ru","focused":true}]"#,
+        );
+        let response = router.dispatch(interaction).await.unwrap();
+
+        let InteractionResponse::ApplicationCommandAutocompleteResult(choices) = response else {
+            panic!("Expected an autocomplete result");
+        };
+        assert_eq!(choices.len(), MAX_CHOICES);
+        assert_eq!(choices[0].name, "!ru0");
+    }
+
+    #[tokio::test]
+    async fn ignore_unregistered_options() {
+        let router = AutocompleteRouter::new().register("tag show", "name", tags);
+
+        let interaction = autocomplete(r#"[{"name":"owner","type":3,"value":"a","focused":true}]"#);
+        assert!(router.dispatch(interaction).await.is_none());
+    }
+}
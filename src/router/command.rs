@@ -0,0 +1,221 @@
+use crate::model::application_command::{
+    ApplicationCommandData, ApplicationCommandType, CommandDataOption, CommandOptionValue,
+};
+use crate::model::interactions::CommandInteraction;
+use crate::model::response::InteractionResponse;
+use crate::rest::webhook::InteractionWebhook;
+use crate::rest::RestClient;
+use crate::router::options::CommandOptions;
+use crate::router::BoxFuture;
+use lambda_http::tracing;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Everything a command handler receives about the invocation.
+pub struct CommandContext {
+    pub interaction: CommandInteraction,
+    /// The options of the invoked leaf command, below any subcommand group and subcommand.
+    pub options: CommandOptions,
+    /// Acknowledges the interaction, edits the response and sends follow-ups, see
+    /// [`InteractionWebhook`] for finishing slow work.
+    pub webhook: InteractionWebhook,
+}
+
+/// An async handler for an application command.
+///
+/// Implemented for any `Fn(CommandContext) -> impl Future<Output = InteractionResponse>`,
+/// so plain `async fn`s can be registered directly.
+pub trait CommandHandler: Send + Sync {
+    fn call(&self, ctx: CommandContext) -> BoxFuture<InteractionResponse>;
+}
+
+impl<F, Fut> CommandHandler for F
+where
+    F: Fn(CommandContext) -> Fut + Send + Sync,
+    Fut: Future<Output = InteractionResponse> + Send + 'static,
+{
+    fn call(&self, ctx: CommandContext) -> BoxFuture<InteractionResponse> {
+        Box::pin(self(ctx))
+    }
+}
+
+/// Routes application command interactions to handlers registered by command type and name.
+#[derive(Default)]
+pub struct CommandRouter {
+    handlers: HashMap<(ApplicationCommandType, String), Box<dyn CommandHandler>>,
+}
+
+impl CommandRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register<H>(mut self, command_type: ApplicationCommandType, name: &str, handler: H) -> Self
+    where
+        H: CommandHandler + 'static,
+    {
+        self.handlers
+            .insert((command_type, name.to_string()), Box::new(handler));
+        self
+    }
+
+    /// Registers a handler for a slash command.
+    ///
+    /// Subcommands are registered by their full path separated by spaces, e.g.
+    /// `"config set"` or `"config roles add"`, and each gets its own handler.
+    pub fn chat_input<H>(self, name: &str, handler: H) -> Self
+    where
+        H: CommandHandler + 'static,
+    {
+        self.register(ApplicationCommandType::ChatInput, name, handler)
+    }
+
+    /// Registers a handler for a user context menu command.
+    pub fn user<H>(self, name: &str, handler: H) -> Self
+    where
+        H: CommandHandler + 'static,
+    {
+        self.register(ApplicationCommandType::User, name, handler)
+    }
+
+    /// Registers a handler for a message context menu command.
+    pub fn message<H>(self, name: &str, handler: H) -> Self
+    where
+        H: CommandHandler + 'static,
+    {
+        self.register(ApplicationCommandType::Message, name, handler)
+    }
+
+    /// Runs the handler registered for the interaction's command.
+    ///
+    /// Returns `None` if no handler is registered for the command.
+    pub async fn dispatch(
+        &self,
+        interaction: CommandInteraction,
+        rest: &RestClient,
+    ) -> Option<InteractionResponse> {
+        let (path, options) = resolve_path(&interaction.data);
+        let key = (interaction.data.command_type, path);
+        let Some(handler) = self.handlers.get(&key) else {
+            tracing::warn!("No handler registered for command {:?} {}", key.0, key.1);
+            return None;
+        };
+
+        let options = CommandOptions::new(options.to_vec(), interaction.data.resolved.clone());
+        let webhook = InteractionWebhook::for_interaction(rest, &interaction.base);
+        Some(
+            handler
+                .call(CommandContext {
+                    interaction,
+                    options,
+                    webhook,
+                })
+                .await,
+        )
+    }
+}
+
+/// Walks the subcommand group and subcommand options of a command, returning the
+/// space separated path to the invoked leaf command and the leaf's own options.
+pub fn resolve_path(data: &ApplicationCommandData) -> (String, &[CommandDataOption]) {
+    let mut path = data.name.clone();
+    let mut options = data.options.as_slice();
+
+    while let [option] = options {
+        let (CommandOptionValue::SubCommand(nested) | CommandOptionValue::SubCommandGroup(nested)) =
+            &option.value
+        else {
+            break;
+        };
+        path.push(' ');
+        path.push_str(&option.name);
+        options = nested;
+    }
+
+    (path, options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandContext, CommandRouter};
+    use crate::model::primitives::Snowflake;
+    use crate::model::interactions::{Interaction, TestInteraction};
+    use serde_json::{json, Value};
+    use crate::model::response::InteractionResponse;
+    use crate::rest::RestClient;
+
+    fn command(name: &str, command_type: u8) -> crate::model::interactions::CommandInteraction {
+        command_with_options(name, command_type, "[]")
+    }
+
+    fn command_with_options(
+        name: &str,
+        command_type: u8,
+        options: &str,
+    ) -> crate::model::interactions::CommandInteraction {
+        let options: Value = serde_json::from_str(options).unwrap();
+        let data = json!({"id": "1", "name": name, "type": command_type, "options": options});
+        match TestInteraction::new(2, data).build() {
+            Interaction::ApplicationCommand(command) => command,
+            other => panic!("Expected an application command, got {:?}", other),
+        }
+    }
+
+    async fn ping(_: CommandContext) -> InteractionResponse {
+        InteractionResponse::message("Pong!")
+    }
+
+    async fn report(_: CommandContext) -> InteractionResponse {
+        InteractionResponse::message("Reported")
+    }
+
+    #[tokio::test]
+    async fn dispatch_by_name_and_type() {
+        let rest = RestClient::unauthenticated();
+        let router = CommandRouter::new()
+            .chat_input("ping", ping)
+            .user("ping", report);
+
+        let response = router.dispatch(command("ping", 1), &rest).await.unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "Pong!");
+
+        let response = router.dispatch(command("ping", 2), &rest).await.unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "Reported");
+
+        assert!(router.dispatch(command("ping", 3), &rest).await.is_none());
+        assert!(router.dispatch(command("unknown", 1), &rest).await.is_none());
+    }
+
+    async fn roles_add(ctx: CommandContext) -> InteractionResponse {
+        let role = ctx.options.get::<Snowflake>("role").unwrap();
+        InteractionResponse::message(format!("role={}", role))
+    }
+
+    async fn config_set(ctx: CommandContext) -> InteractionResponse {
+        InteractionResponse::message(format!("set {} options", ctx.options.iter().count()))
+    }
+
+    #[tokio::test]
+    async fn dispatch_subcommands() {
+        let rest = RestClient::unauthenticated();
+        let router = CommandRouter::new()
+            .chat_input("config set", config_set)
+            .chat_input("config roles add", roles_add);
+
+        let grouped = r#"[{"name":"roles","type":2,"options":[{"name":"add","type":1,"options":[{"name":"role","type":8,"value":"42"}]}]}]"#;
+        let response = router
+            .dispatch(command_with_options("config", 1, grouped), &rest)
+            .await
+            .unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "role=42");
+
+        let plain = r#"[{"name":"set","type":1}]"#;
+        let response = router
+            .dispatch(command_with_options("config", 1, plain), &rest)
+            .await
+            .unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "set 0 options");
+
+        assert!(router.dispatch(command("config", 1), &rest).await.is_none());
+    }
+}
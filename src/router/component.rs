@@ -0,0 +1,135 @@
+use crate::model::interactions::ComponentInteraction;
+use crate::model::response::InteractionResponse;
+use crate::rest::webhook::InteractionWebhook;
+use crate::rest::RestClient;
+use crate::router::custom_id::CustomIdPattern;
+use crate::router::BoxFuture;
+use lambda_http::tracing;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Everything a component handler receives about the interaction.
+pub struct ComponentContext {
+    pub interaction: ComponentInteraction,
+    /// The segments captured by the matched `custom_id` pattern.
+    pub params: HashMap<String, String>,
+    /// Acknowledges the interaction, edits the response and sends follow-ups, see
+    /// [`InteractionWebhook`] for finishing slow work.
+    pub webhook: InteractionWebhook,
+}
+
+impl ComponentContext {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+}
+
+/// An async handler for button and select menu interactions.
+pub trait ComponentHandler: Send + Sync {
+    fn call(&self, ctx: ComponentContext) -> BoxFuture<InteractionResponse>;
+}
+
+impl<F, Fut> ComponentHandler for F
+where
+    F: Fn(ComponentContext) -> Fut + Send + Sync,
+    Fut: Future<Output = InteractionResponse> + Send + 'static,
+{
+    fn call(&self, ctx: ComponentContext) -> BoxFuture<InteractionResponse> {
+        Box::pin(self(ctx))
+    }
+}
+
+/// Routes message component interactions to handlers by `custom_id` pattern.
+///
+/// Patterns are tried in registration order and the first match wins, so register
+/// specific patterns before broad prefixes.
+#[derive(Default)]
+pub struct ComponentRouter {
+    routes: Vec<(CustomIdPattern, Box<dyn ComponentHandler>)>,
+}
+
+impl ComponentRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for `custom_id`s matching `pattern`, see [`CustomIdPattern`].
+    pub fn register<H>(mut self, pattern: &str, handler: H) -> Self
+    where
+        H: ComponentHandler + 'static,
+    {
+        self.routes.push((CustomIdPattern::new(pattern), Box::new(handler)));
+        self
+    }
+
+    /// Runs the first handler whose pattern matches the interaction's `custom_id`.
+    ///
+    /// Returns `None` if no pattern matches.
+    pub async fn dispatch(
+        &self,
+        interaction: ComponentInteraction,
+        rest: &RestClient,
+    ) -> Option<InteractionResponse> {
+        let custom_id = interaction.data.custom_id.as_str();
+        let Some((params, handler)) = self
+            .routes
+            .iter()
+            .find_map(|(pattern, handler)| pattern.matches(custom_id).map(|params| (params, handler)))
+        else {
+            tracing::warn!("No component handler matches custom_id {}", custom_id);
+            return None;
+        };
+
+        let webhook = InteractionWebhook::for_interaction(rest, &interaction.base);
+        Some(
+            handler
+                .call(ComponentContext {
+                    interaction,
+                    params,
+                    webhook,
+                })
+                .await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ComponentContext, ComponentRouter};
+    use crate::model::interactions::{ComponentInteraction, Interaction, TestInteraction};
+    use serde_json::json;
+    use crate::model::response::InteractionResponse;
+    use crate::rest::RestClient;
+
+    fn component(custom_id: &str) -> ComponentInteraction {
+        let data = json!({"custom_id": custom_id, "component_type": 2});
+        match TestInteraction::new(3, data).build() {
+            Interaction::MessageComponent(component) => component,
+            other => panic!("Expected a component interaction, got {:?}", other),
+        }
+    }
+
+    async fn close(ctx: ComponentContext) -> InteractionResponse {
+        InteractionResponse::message(format!("closed {}", ctx.param("id").unwrap()))
+    }
+
+    async fn other(ctx: ComponentContext) -> InteractionResponse {
+        InteractionResponse::message(format!("other {}", ctx.param("*").unwrap()))
+    }
+
+    #[tokio::test]
+    async fn dispatch_first_matching_pattern() {
+        let rest = RestClient::unauthenticated();
+        let router = ComponentRouter::new()
+            .register("ticket:close:{id}", close)
+            .register("ticket:*", other);
+
+        let response = router.dispatch(component("ticket:close:42"), &rest).await.unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "closed 42");
+
+        let response = router.dispatch(component("ticket:reopen:42"), &rest).await.unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "other reopen:42");
+
+        assert!(router.dispatch(component("poll:vote:1"), &rest).await.is_none());
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+/// The separator between segments of a `custom_id`.
+pub const SEPARATOR: char = ':';
+
+/// The capture name under which a trailing `*` stores the rest of a `custom_id`.
+pub const REST: &str = "*";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Capture(String),
+    Rest,
+}
+
+/// A pattern matched against component and modal `custom_id`s.
+///
+/// Patterns are split on `:` into segments. A segment is either a literal, a `{name}`
+/// capture matching exactly one segment, or a trailing `*` matching any remaining
+/// segments, so `ticket:close:{id}` matches `ticket:close:42` with `id = "42"` and
+/// `ticket:*` matches every `custom_id` starting with `ticket:`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CustomIdPattern {
+    pattern: String,
+    segments: Vec<Segment>,
+}
+
+impl CustomIdPattern {
+    /// Parses a pattern.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `*` is used anywhere but as the last segment, or a capture has no name,
+    /// since patterns are fixed when handlers are registered.
+    pub fn new(pattern: &str) -> Self {
+        let parts: Vec<&str> = pattern.split(SEPARATOR).collect();
+        let segments = parts
+            .iter()
+            .enumerate()
+            .map(|(index, part)| {
+                if *part == REST {
+                    assert!(
+                        index == parts.len() - 1,
+                        "'*' must be the last segment of custom_id pattern '{}'",
+                        pattern
+                    );
+                    Segment::Rest
+                } else if let Some(name) = part.strip_prefix('{').and_then(|p| p.strip_suffix('}')) {
+                    assert!(!name.is_empty(), "Unnamed capture in custom_id pattern '{}'", pattern);
+                    Segment::Capture(name.to_string())
+                } else {
+                    Segment::Literal(part.to_string())
+                }
+            })
+            .collect();
+
+        CustomIdPattern {
+            pattern: pattern.to_string(),
+            segments,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.pattern
+    }
+
+    /// Matches a `custom_id` against the pattern, returning the captured segments.
+    pub fn matches(&self, custom_id: &str) -> Option<HashMap<String, String>> {
+        let mut params = HashMap::new();
+        let mut rest = Some(custom_id);
+
+        for segment in &self.segments {
+            if let Segment::Rest = segment {
+                params.insert(REST.to_string(), rest.unwrap_or_default().to_string());
+                return Some(params);
+            }
+
+            let (part, remaining) = match rest?.split_once(SEPARATOR) {
+                Some((part, remaining)) => (part, Some(remaining)),
+                None => (rest?, None),
+            };
+            rest = remaining;
+
+            match segment {
+                Segment::Literal(literal) if literal == part => {}
+                Segment::Capture(name) => {
+                    params.insert(name.clone(), part.to_string());
+                }
+                _ => return None,
+            }
+        }
+
+        rest.is_none().then_some(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CustomIdPattern;
+
+    #[test]
+    fn match_literals_and_captures() {
+        let pattern = CustomIdPattern::new("ticket:close:{id}");
+
+        let params = pattern.matches("ticket:close:42").unwrap();
+        assert_eq!(params.get("id").map(String::as_str), Some("42"));
+
+        assert!(pattern.matches("ticket:close").is_none());
+        assert!(pattern.matches("ticket:close:42:extra").is_none());
+        assert!(pattern.matches("ticket:open:42").is_none());
+    }
+
+    #[test]
+    fn match_prefixes() {
+        let pattern = CustomIdPattern::new("ticket:{action}:*");
+
+        let params = pattern.matches("ticket:assign:42:7").unwrap();
+        assert_eq!(params.get("action").map(String::as_str), Some("assign"));
+        assert_eq!(params.get("*").map(String::as_str), Some("42:7"));
+
+        assert!(CustomIdPattern::new("ticket:*").matches("ticket").is_some());
+        assert!(CustomIdPattern::new("ticket:*").matches("poll:1").is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn reject_wildcard_before_end() {
+        CustomIdPattern::new("ticket:*:close");
+    }
+}
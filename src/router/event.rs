@@ -0,0 +1,109 @@
+use crate::model::event::{Event, EventType, EventWebhook};
+use crate::model::primitives::Snowflake;
+use crate::router::BoxFuture;
+use lambda_http::tracing;
+use std::collections::HashMap;
+use std::future::Future;
+
+/// Everything an event handler receives about the webhook event.
+pub struct EventContext {
+    pub application_id: Snowflake,
+    pub event: Event,
+}
+
+/// An async handler for webhook events.
+///
+/// Handlers cannot change the response, Discord only expects a `204 No Content`.
+pub trait EventHandler: Send + Sync {
+    fn call(&self, ctx: EventContext) -> BoxFuture<()>;
+}
+
+impl<F, Fut> EventHandler for F
+where
+    F: Fn(EventContext) -> Fut + Send + Sync,
+    Fut: Future<Output = ()> + Send + 'static,
+{
+    fn call(&self, ctx: EventContext) -> BoxFuture<()> {
+        Box::pin(self(ctx))
+    }
+}
+
+/// Routes webhook events to handlers by event type.
+#[derive(Default)]
+pub struct EventRouter {
+    routes: HashMap<EventType, Box<dyn EventHandler>>,
+}
+
+impl EventRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the handler for events of `event_type`, replacing any previous one.
+    pub fn on<H>(mut self, event_type: EventType, handler: H) -> Self
+    where
+        H: EventHandler + 'static,
+    {
+        self.routes.insert(event_type, Box::new(handler));
+        self
+    }
+
+    /// Runs the handler registered for the event's type.
+    ///
+    /// Returns `false` if the event type is unknown or has no handler.
+    pub async fn dispatch(&self, webhook: EventWebhook) -> bool {
+        let Some(handler) = webhook
+            .event
+            .data
+            .event_type()
+            .and_then(|event_type| self.routes.get(&event_type))
+        else {
+            tracing::info!("No event handler for {:?}", webhook.event.data.event_type());
+            return false;
+        };
+
+        handler
+            .call(EventContext {
+                application_id: webhook.application_id,
+                event: webhook.event,
+            })
+            .await;
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventContext, EventRouter};
+    use crate::model::event::{EventData, EventType, EventWebhook, WebhookEvent};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    fn webhook(event_type: &str, data: &str) -> EventWebhook {
+        let body = format!(
+            r#"{{"version":1,"application_id":"1096551423958855810","type":1,"event":{{"type":"{}","timestamp":"2024-10-18T14:42:53.064834","data":{}}}}}"#,
+            event_type, data
+        );
+        match serde_json::from_str(&body).unwrap() {
+            WebhookEvent::Event(webhook) => webhook,
+            other => panic!("Expected an event, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_by_event_type() {
+        let enrollments = Arc::new(AtomicUsize::new(0));
+        let counter = enrollments.clone();
+        let router = EventRouter::new().on(EventType::QuestUserEnrollment, move |ctx: EventContext| {
+            let counter = counter.clone();
+            async move {
+                assert!(matches!(ctx.event.data, EventData::QuestUserEnrollment(_)));
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        assert!(router.dispatch(webhook("QUEST_USER_ENROLLMENT", "{}")).await);
+        assert!(!router.dispatch(webhook("SOMETHING_NEW", "{}")).await);
+        assert_eq!(enrollments.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,13 @@
+pub mod autocomplete;
+pub mod command;
+pub mod component;
+pub mod custom_id;
+pub mod event;
+pub mod modal;
+pub mod options;
+
+use std::future::Future;
+use std::pin::Pin;
+
+/// A boxed, sendable future returned by interaction handlers.
+pub type BoxFuture<T> = Pin<Box<dyn Future<Output = T> + Send>>;
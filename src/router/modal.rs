@@ -0,0 +1,237 @@
+use crate::model::interactions::ModalSubmitInteraction;
+use crate::model::message::Component;
+use crate::model::response::InteractionResponse;
+use crate::rest::webhook::InteractionWebhook;
+use crate::rest::RestClient;
+use crate::router::custom_id::CustomIdPattern;
+use crate::router::BoxFuture;
+use lambda_http::tracing;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::str::FromStr;
+
+/// Everything a modal handler receives about the submission.
+pub struct ModalContext {
+    pub interaction: ModalSubmitInteraction,
+    /// The segments captured by the matched `custom_id` pattern.
+    pub params: HashMap<String, String>,
+    pub fields: ModalFields,
+    /// Acknowledges the interaction, edits the response and sends follow-ups, see
+    /// [`InteractionWebhook`] for finishing slow work.
+    pub webhook: InteractionWebhook,
+}
+
+impl ModalContext {
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(String::as_str)
+    }
+}
+
+/// The submitted text input values of a modal, keyed by the inputs' `custom_id`.
+#[derive(Debug, Clone, Default)]
+pub struct ModalFields {
+    values: HashMap<String, String>,
+}
+
+impl ModalFields {
+    pub fn from_components(components: &[Component]) -> Self {
+        let mut values = HashMap::new();
+        collect_values(components, &mut values);
+        ModalFields { values }
+    }
+
+    /// Returns the value of a text input.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no text input with the given `custom_id` was submitted.
+    pub fn get(&self, custom_id: &str) -> Result<&str, ModalFieldError> {
+        self.values
+            .get(custom_id)
+            .map(String::as_str)
+            .ok_or_else(|| ModalFieldError::Missing {
+                custom_id: custom_id.to_string(),
+            })
+    }
+
+    /// Returns the value of a text input, or `None` if it was left empty or not submitted.
+    pub fn get_optional(&self, custom_id: &str) -> Option<&str> {
+        self.values
+            .get(custom_id)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Parses the value of a text input into `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is missing or its value does not parse.
+    pub fn parse<T>(&self, custom_id: &str) -> Result<T, ModalFieldError>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        let value = self.get(custom_id)?;
+        value.trim().parse().map_err(|e: T::Err| ModalFieldError::Invalid {
+            custom_id: custom_id.to_string(),
+            value: value.to_string(),
+            reason: e.to_string(),
+        })
+    }
+}
+
+fn collect_values(components: &[Component], values: &mut HashMap<String, String>) {
+    for component in components {
+        if let (Some(custom_id), Some(value)) = (&component.custom_id, &component.value) {
+            values.insert(custom_id.clone(), value.clone());
+        }
+        if let Some(children) = &component.components {
+            collect_values(children, values);
+        }
+        if let Some(child) = &component.component {
+            collect_values(std::slice::from_ref(child.as_ref()), values);
+        }
+    }
+}
+
+/// An error returned when a text input value cannot be extracted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModalFieldError {
+    Missing { custom_id: String },
+    Invalid {
+        custom_id: String,
+        value: String,
+        reason: String,
+    },
+}
+
+impl Display for ModalFieldError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ModalFieldError::Missing { custom_id } => write!(f, "Missing text input '{}'", custom_id),
+            ModalFieldError::Invalid {
+                custom_id,
+                value,
+                reason,
+            } => write!(f, "Invalid value '{}' for text input '{}': {}", value, custom_id, reason),
+        }
+    }
+}
+
+impl std::error::Error for ModalFieldError {}
+
+/// An async handler for modal submissions.
+pub trait ModalHandler: Send + Sync {
+    fn call(&self, ctx: ModalContext) -> BoxFuture<InteractionResponse>;
+}
+
+impl<F, Fut> ModalHandler for F
+where
+    F: Fn(ModalContext) -> Fut + Send + Sync,
+    Fut: Future<Output = InteractionResponse> + Send + 'static,
+{
+    fn call(&self, ctx: ModalContext) -> BoxFuture<InteractionResponse> {
+        Box::pin(self(ctx))
+    }
+}
+
+/// Routes modal submissions to handlers by `custom_id` pattern.
+///
+/// Patterns are tried in registration order and the first match wins.
+#[derive(Default)]
+pub struct ModalRouter {
+    routes: Vec<(CustomIdPattern, Box<dyn ModalHandler>)>,
+}
+
+impl ModalRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for modal `custom_id`s matching `pattern`, see [`CustomIdPattern`].
+    pub fn register<H>(mut self, pattern: &str, handler: H) -> Self
+    where
+        H: ModalHandler + 'static,
+    {
+        self.routes.push((CustomIdPattern::new(pattern), Box::new(handler)));
+        self
+    }
+
+    /// Runs the first handler whose pattern matches the modal's `custom_id`.
+    ///
+    /// Returns `None` if no pattern matches.
+    pub async fn dispatch(
+        &self,
+        interaction: ModalSubmitInteraction,
+        rest: &RestClient,
+    ) -> Option<InteractionResponse> {
+        let custom_id = interaction.data.custom_id.as_str();
+        let Some((params, handler)) = self
+            .routes
+            .iter()
+            .find_map(|(pattern, handler)| pattern.matches(custom_id).map(|params| (params, handler)))
+        else {
+            tracing::warn!("No modal handler matches custom_id {}", custom_id);
+            return None;
+        };
+
+        let fields = ModalFields::from_components(&interaction.data.components);
+        let webhook = InteractionWebhook::for_interaction(rest, &interaction.base);
+        Some(
+            handler
+                .call(ModalContext {
+                    interaction,
+                    params,
+                    fields,
+                    webhook,
+                })
+                .await,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ModalContext, ModalFieldError, ModalRouter};
+    use crate::model::interactions::{Interaction, ModalSubmitInteraction, TestInteraction};
+    use serde_json::json;
+    use crate::model::response::InteractionResponse;
+    use crate::rest::RestClient;
+
+    fn modal_submit(custom_id: &str) -> ModalSubmitInteraction {
+        let data = json!({
+            "custom_id": custom_id,
+            "components": [
+                {"type": 1, "components": [{"type": 4, "custom_id": "age", "value": " 42 "}]},
+                {"type": 18, "component": {"type": 4, "custom_id": "name", "value": "Ferris"}},
+                {"type": 1, "components": [{"type": 4, "custom_id": "notes", "value": ""}]}
+            ]
+        });
+        match TestInteraction::new(5, data).build() {
+            Interaction::ModalSubmit(modal) => modal,
+            other => panic!("Expected a modal submit, got {:?}", other),
+        }
+    }
+
+    async fn profile(ctx: ModalContext) -> InteractionResponse {
+        let age = ctx.fields.parse::<u8>("age").unwrap();
+        let name = ctx.fields.get("name").unwrap();
+        assert_eq!(ctx.fields.get_optional("notes"), None);
+        assert!(matches!(ctx.fields.parse::<u8>("name"), Err(ModalFieldError::Invalid { .. })));
+        assert!(matches!(ctx.fields.get("email"), Err(ModalFieldError::Missing { .. })));
+        InteractionResponse::message(format!("{} {} {}", ctx.param("user").unwrap(), name, age))
+    }
+
+    #[tokio::test]
+    async fn dispatch_and_extract_fields() {
+        let rest = RestClient::unauthenticated();
+        let router = ModalRouter::new().register("profile:{user}", profile);
+
+        let response = router.dispatch(modal_submit("profile:7"), &rest).await.unwrap();
+        assert_eq!(serde_json::to_value(&response).unwrap()["data"]["content"], "7 Ferris 42");
+
+        assert!(router.dispatch(modal_submit("survey"), &rest).await.is_none());
+    }
+}
@@ -0,0 +1,282 @@
+use crate::model::application_command::{CommandDataOption, CommandOptionType, CommandOptionValue};
+use crate::model::channel::Channel;
+use crate::model::guild::{GuildMember, Role};
+use crate::model::message::Attachment;
+use crate::model::primitives::Snowflake;
+use crate::model::resolved::Resolved;
+use crate::model::user::User;
+use std::fmt::{Display, Formatter};
+
+/// The options passed to a command handler, with typed accessors such as
+/// `get::<i64>("count")` or `get_optional::<User>("target")`.
+#[derive(Debug, Clone, Default)]
+pub struct CommandOptions {
+    options: Vec<CommandDataOption>,
+    resolved: Option<Resolved>,
+}
+
+impl CommandOptions {
+    pub fn new(options: Vec<CommandDataOption>, resolved: Option<Resolved>) -> Self {
+        CommandOptions { options, resolved }
+    }
+
+    /// Returns the option with the given name converted to `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the option was not supplied, has a different type, or refers
+    /// to an object missing from the resolved data.
+    pub fn get<T: FromOption>(&self, name: &str) -> Result<T, OptionError> {
+        self.get_optional(name)?.ok_or_else(|| OptionError::Missing {
+            name: name.to_string(),
+        })
+    }
+
+    /// Like [`CommandOptions::get`], but returns `Ok(None)` if the option was not supplied.
+    pub fn get_optional<T: FromOption>(&self, name: &str) -> Result<Option<T>, OptionError> {
+        self.option(name)
+            .map(|option| T::from_option(option, self.resolved.as_ref()))
+            .transpose()
+    }
+
+    pub fn option(&self, name: &str) -> Option<&CommandDataOption> {
+        self.options.iter().find(|option| option.name == name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CommandDataOption> {
+        self.options.iter()
+    }
+
+    pub fn resolved(&self) -> Option<&Resolved> {
+        self.resolved.as_ref()
+    }
+}
+
+/// An error returned when an option cannot be extracted as the requested type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OptionError {
+    /// The option was not supplied with the command.
+    Missing { name: String },
+    /// The option has a different type than the one requested.
+    WrongType {
+        name: String,
+        expected: &'static str,
+        found: CommandOptionType,
+    },
+    /// The option refers to an object Discord did not include in the resolved data.
+    Unresolved { name: String },
+}
+
+impl OptionError {
+    pub fn wrong_type(option: &CommandDataOption, expected: &'static str) -> Self {
+        OptionError::WrongType {
+            name: option.name.clone(),
+            expected,
+            found: option.option_type(),
+        }
+    }
+
+    pub fn unresolved(option: &CommandDataOption) -> Self {
+        OptionError::Unresolved {
+            name: option.name.clone(),
+        }
+    }
+}
+
+impl Display for OptionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionError::Missing { name } => write!(f, "Missing option '{}'", name),
+            OptionError::WrongType {
+                name,
+                expected,
+                found,
+            } => write!(f, "Option '{}' is a {:?}, expected {}", name, found, expected),
+            OptionError::Unresolved { name } => {
+                write!(f, "Option '{}' is missing from the resolved data", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OptionError {}
+
+/// Conversion from a command option into a handler-facing type.
+pub trait FromOption: Sized {
+    fn from_option(option: &CommandDataOption, resolved: Option<&Resolved>) -> Result<Self, OptionError>;
+}
+
+impl FromOption for String {
+    fn from_option(option: &CommandDataOption, _: Option<&Resolved>) -> Result<Self, OptionError> {
+        match &option.value {
+            CommandOptionValue::String(value) => Ok(value.clone()),
+            _ => Err(OptionError::wrong_type(option, "a string")),
+        }
+    }
+}
+
+impl FromOption for i64 {
+    fn from_option(option: &CommandDataOption, _: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::Integer(value) => Ok(value),
+            _ => Err(OptionError::wrong_type(option, "an integer")),
+        }
+    }
+}
+
+impl FromOption for f64 {
+    fn from_option(option: &CommandDataOption, _: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::Number(value) => Ok(value),
+            _ => Err(OptionError::wrong_type(option, "a number")),
+        }
+    }
+}
+
+impl FromOption for bool {
+    fn from_option(option: &CommandDataOption, _: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::Boolean(value) => Ok(value),
+            _ => Err(OptionError::wrong_type(option, "a boolean")),
+        }
+    }
+}
+
+impl FromOption for Snowflake {
+    fn from_option(option: &CommandDataOption, _: Option<&Resolved>) -> Result<Self, OptionError> {
+        match &option.value {
+            CommandOptionValue::User(id)
+            | CommandOptionValue::Channel(id)
+            | CommandOptionValue::Role(id)
+            | CommandOptionValue::Mentionable(id)
+            | CommandOptionValue::Attachment(id) => Ok(id.clone()),
+            _ => Err(OptionError::wrong_type(option, "a snowflake")),
+        }
+    }
+}
+
+/// Looks up the object an option refers to in the resolved data.
+fn resolve<'a, T>(
+    option: &CommandDataOption,
+    resolved: Option<&'a Resolved>,
+    expected: &'static str,
+    lookup: impl FnOnce(&'a Resolved, &Snowflake) -> Option<&'a T>,
+) -> Result<T, OptionError>
+where
+    T: Clone + 'a,
+{
+    let id = Snowflake::from_option(option, None)
+        .map_err(|_| OptionError::wrong_type(option, expected))?;
+
+    resolved
+        .and_then(|resolved| lookup(resolved, &id))
+        .cloned()
+        .ok_or_else(|| OptionError::unresolved(option))
+}
+
+impl FromOption for User {
+    fn from_option(option: &CommandDataOption, resolved: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::User(_) | CommandOptionValue::Mentionable(_) => {
+                resolve(option, resolved, "a user", Resolved::user)
+            }
+            _ => Err(OptionError::wrong_type(option, "a user")),
+        }
+    }
+}
+
+impl FromOption for GuildMember {
+    fn from_option(option: &CommandDataOption, resolved: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::User(_) | CommandOptionValue::Mentionable(_) => {
+                resolve(option, resolved, "a member", Resolved::member)
+            }
+            _ => Err(OptionError::wrong_type(option, "a member")),
+        }
+    }
+}
+
+impl FromOption for Role {
+    fn from_option(option: &CommandDataOption, resolved: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::Role(_) | CommandOptionValue::Mentionable(_) => {
+                resolve(option, resolved, "a role", Resolved::role)
+            }
+            _ => Err(OptionError::wrong_type(option, "a role")),
+        }
+    }
+}
+
+impl FromOption for Channel {
+    fn from_option(option: &CommandDataOption, resolved: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::Channel(_) => resolve(option, resolved, "a channel", Resolved::channel),
+            _ => Err(OptionError::wrong_type(option, "a channel")),
+        }
+    }
+}
+
+impl FromOption for Attachment {
+    fn from_option(option: &CommandDataOption, resolved: Option<&Resolved>) -> Result<Self, OptionError> {
+        match option.value {
+            CommandOptionValue::Attachment(_) => {
+                resolve(option, resolved, "an attachment", Resolved::attachment)
+            }
+            _ => Err(OptionError::wrong_type(option, "an attachment")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommandOptions, OptionError};
+    use crate::model::application_command::{ApplicationCommandData, CommandOptionType};
+    use crate::model::message::Attachment;
+    use crate::model::user::User;
+
+    fn options() -> CommandOptions {
+        let data = r#"{"id":"1","name":"upload","type":1,"options":[
+            {"name":"count","type":4,"value":3},
+            {"name":"target","type":6,"value":"643945264868098049"},
+            {"name":"file","type":11,"value":"99"},
+            {"name":"ghost","type":6,"value":"1"}
+        ],"resolved":{
+            "users":{"643945264868098049":{"id":"643945264868098049","username":"someone","discriminator":"0"}},
+            "attachments":{"99":{"id":"99","filename":"log.txt","size":12,"url":"https://cdn.discordapp.com/log.txt","proxy_url":"https://media.discordapp.net/log.txt"}}
+        }}"#;
+        let data: ApplicationCommandData = serde_json::from_str(data).unwrap();
+        CommandOptions::new(data.options, data.resolved)
+    }
+
+    #[test]
+    fn get_typed_options() {
+        let options = options();
+
+        assert_eq!(options.get::<i64>("count"), Ok(3));
+        assert_eq!(options.get::<User>("target").unwrap().username, "someone");
+        assert_eq!(options.get::<Attachment>("file").unwrap().filename, "log.txt");
+        assert_eq!(options.get_optional::<String>("reason"), Ok(None));
+    }
+
+    #[test]
+    fn report_extraction_errors() {
+        let options = options();
+
+        assert_eq!(
+            options.get::<String>("reason"),
+            Err(OptionError::Missing { name: "reason".to_string() })
+        );
+        assert_eq!(
+            options.get::<String>("count"),
+            Err(OptionError::WrongType {
+                name: "count".to_string(),
+                expected: "a string",
+                found: CommandOptionType::Integer,
+            })
+        );
+        assert_eq!(
+            options.get::<User>("ghost").unwrap_err(),
+            OptionError::Unresolved { name: "ghost".to_string() }
+        );
+    }
+}
@@ -1,13 +1,23 @@
-use crate::{security, ApiError, App, Pong};
+use crate::security::{ReplayProtection, VerificationError, SIGNATURE_HEADER, TIMESTAMP_HEADER};
+use crate::{security, ApiError, App, Application};
 use axum::body::{Body, Bytes};
-use axum::extract::State;
+use axum::extract::{Path, State};
+use axum::Extension;
+use http_body_util::LengthLimitError;
 use axum::http::{header, HeaderMap};
 use axum::response::Response;
 use axum::debug_handler;
 use std::str::from_utf8;
+use std::sync::Arc;
 use lambda_http::request::RequestContext::ApiGatewayV1;
 use lambda_http::tracing;
-use crate::model::interactions::{FullInteraction, Interaction};
+use crate::model::event::{EventData, WebhookEvent};
+use crate::model::interactions::Interaction;
+use crate::model::primitives::Snowflake;
+use crate::model::response::{InteractionResponse, MessageFlags};
+use crate::rest::webhook::InteractionWebhook;
+use std::future::Future;
+use std::time::Duration;
 
 pub async fn mw_sample(
     req: axum::extract::Request,
@@ -33,62 +43,735 @@ fn error_response(message: &str, code: u16) -> Response {
         .unwrap()
 }
 
-#[debug_handler]
-pub async fn ping_handler(
-    key: State<App>,
-    req: axum::http::Request<Body>,
+/// The applications whose public key verified a request, added to the request extensions
+/// by [`verify_signature`].
+#[derive(Clone)]
+pub struct VerifiedApplications(Vec<Arc<Application>>);
+
+impl VerifiedApplications {
+    /// The verified application that should handle an interaction sent for `application_id`.
+    ///
+    /// Prefers an application configured with that ID, falling back to one without an ID.
+    pub fn select(&self, application_id: &Snowflake) -> Option<&Arc<Application>> {
+        self.0
+            .iter()
+            .find(|application| application.application_id.as_ref() == Some(application_id))
+            .or_else(|| self.0.iter().find(|application| application.application_id.is_none()))
+    }
+}
+
+/// Middleware verifying the Ed25519 signature of a request before it reaches the route.
+///
+/// Routes with an `{app}` path parameter are verified against that application only, any
+/// other route against every hosted application. Bodies over the app's size limit are
+/// rejected with a 413 before being buffered, and unverified requests with a 401. Verified
+/// requests carry the buffered raw body on as the request body, so handlers can extract it as
+/// `Bytes`, along with the [`VerifiedApplications`] extension. Stack it onto any route with
+/// `axum::middleware::from_fn_with_state(app, routes::verify_signature)`.
+pub async fn verify_signature(
+    State(app): State<App>,
+    name: Option<Path<String>>,
+    req: axum::extract::Request,
+    next: axum::middleware::Next,
 ) -> Result<Response, Response> {
+    let candidates: Vec<Arc<Application>> = match name {
+        Some(Path(name)) => match app.applications.get(&name) {
+            Some(application) => vec![application.clone()],
+            None => return Err(error_response("Unknown application", 404)),
+        },
+        None => app.applications.values().cloned().collect(),
+    };
 
-    println!("Received request: {:?}", req);
+    let (mut parts, body) = req.into_parts();
+    if content_length(&parts.headers).is_some_and(|length| length > app.max_body_size as u64) {
+        tracing::warn!("Rejected request: Content-Length exceeds {} bytes", app.max_body_size);
+        return Err(error_response("Request body too large", 413));
+    }
 
-    let headers: HeaderMap = req.headers().clone();
-    let body: Body = req.into_body();
-    let body_bytes = axum::body::to_bytes(body, usize::MAX)
+    let body_bytes = axum::body::to_bytes(body, app.max_body_size)
         .await
-        .map_err(|_| error_response("Failed to read body", 500))?;
+        .map_err(|e| {
+            if e.into_inner().is::<LengthLimitError>() {
+                tracing::warn!("Rejected request: body exceeds {} bytes", app.max_body_size);
+                error_response("Request body too large", 413)
+            } else {
+                error_response("Failed to read body", 500)
+            }
+        })?;
 
-    if let Err(e) = validate_request(&headers, &body_bytes, key.public_key.clone()) {
-        println!("Received invalid signature: {}", e);
-        return Err(error_response("Invalid request", 401))
-    }
+    let verified = validate_request(
+        &parts.headers,
+        &body_bytes,
+        &candidates,
+        app.replay_protection.as_deref(),
+    )
+    .map_err(|e| rejection_response(&e))?;
+
+    parts.extensions.insert(VerifiedApplications(verified));
+    let req = axum::extract::Request::from_parts(parts, Body::from(body_bytes));
+    Ok(next.run(req).await)
+}
+
+#[debug_handler]
+pub async fn interaction_handler(
+    app: State<App>,
+    Extension(verified): Extension<VerifiedApplications>,
+    body_bytes: Bytes,
+) -> Result<Response, Response> {
+    let interaction = serde_json::from_slice::<Interaction>(&body_bytes).map_err(|e| {
+        tracing::warn!("Failed to parse interaction: {}", e);
+        error_response("Invalid request", 400)
+    })?;
+    tracing::debug!(
+        "Received {:?} interaction {} for application {}",
+        interaction.interaction_type(),
+        interaction.id(),
+        interaction.application_id()
+    );
 
-    let json_value_result = serde_json::from_slice::<Interaction>(&body_bytes);
-    if let Err(e) = json_value_result {
-        eprintln!("Failed to parse json: {}", e);
-        return Err(error_response("Invalid request", 400))
+    if let Some(replay_protection) = &app.replay_protection {
+        if let Err(e) = replay_protection.check_interaction_id(interaction.id()) {
+            return Err(rejection_response(&e))
+        }
     }
-    
-    let interaction = json_value_result.unwrap();
-    println!("Received interaction: {:#?}", interaction);
+
+    let application = verified.select(interaction.application_id()).ok_or_else(|| {
+        tracing::warn!(
+            "No verified application accepts interactions for {:?}",
+            interaction.application_id()
+        );
+        error_response("Unknown application", 404)
+    })?;
+
+    let deadline = app.deferral_deadline;
+    let response = match interaction {
+        Interaction::Ping(_) => InteractionResponse::Pong,
+        Interaction::ApplicationCommand(command) => {
+            let webhook = InteractionWebhook::for_interaction(&application.rest, &command.base);
+            let dispatch = application.commands.dispatch(command, &application.rest);
+            watch(deadline, InteractionResponse::deferred(), webhook, dispatch)
+                .await
+                .ok_or_else(|| error_response("Unknown command", 404))?
+        }
+        Interaction::Autocomplete(command) => application
+            .autocomplete
+            .dispatch(command)
+            .await
+            .ok_or_else(|| error_response("Unknown autocomplete option", 404))?,
+        Interaction::MessageComponent(component) => {
+            let webhook = InteractionWebhook::for_interaction(&application.rest, &component.base);
+            let dispatch = application.components.dispatch(component, &application.rest);
+            watch(deadline, InteractionResponse::DeferredUpdateMessage, webhook, dispatch)
+                .await
+                .ok_or_else(|| error_response("Unknown component", 404))?
+        }
+        Interaction::ModalSubmit(modal) => {
+            let webhook = InteractionWebhook::for_interaction(&application.rest, &modal.base);
+            let dispatch = application.modals.dispatch(modal, &application.rest);
+            watch(deadline, InteractionResponse::deferred(), webhook, dispatch)
+                .await
+                .ok_or_else(|| error_response("Unknown modal", 404))?
+        }
+    };
 
     Ok(
         Response::builder()
             .header(header::CONTENT_TYPE, "application/json")
             .status(200)
-            .body(Body::from(serde_json::to_string(&Pong { data: 1 }).unwrap()))
+            .body(Body::from(serde_json::to_string(&response).unwrap()))
             .unwrap(),
     )
 }
 
-fn validate_request(headers: &HeaderMap, body_bytes: &Bytes, public_key: String) -> Result<(), String> {
-    
-    let signature = headers
-        .get("x-signature-ed25519")
-        .ok_or("Invalid signature")?;
-    let timestamp = headers
-        .get("x-signature-timestamp")
-        .ok_or("Invalid timestamp")?;
+/// Runs a handler dispatch, acknowledging the interaction with `deferral` through the
+/// callback endpoint if it has not finished within `deadline`. The handler is still awaited
+/// and its response sent through the interaction webhook, see [`complete_deferral`], before
+/// `deferral` is returned, since a Lambda invocation is frozen once it has responded.
+///
+/// Without a deadline the dispatch is simply awaited.
+async fn watch<F>(
+    deadline: Option<Duration>,
+    deferral: InteractionResponse,
+    webhook: InteractionWebhook,
+    dispatch: F,
+) -> Option<InteractionResponse>
+where
+    F: Future<Output = Option<InteractionResponse>>,
+{
+    let Some(deadline) = deadline else {
+        return dispatch.await;
+    };
 
+    let mut dispatch = Box::pin(dispatch);
+    if let Ok(response) = tokio::time::timeout(deadline, &mut dispatch).await {
+        return response;
+    }
+
+    tracing::info!("Handler did not respond within {:?}, deferring", deadline);
+    if let Err(e) = webhook.create_response(&deferral).await {
+        // Answer with the handler's own response instead, in case Discord still accepts it.
+        tracing::error!("Failed to defer the interaction: {}", e);
+        return dispatch.await;
+    }
 
+    if let Some(response) = dispatch.await {
+        let updating = matches!(deferral, InteractionResponse::DeferredUpdateMessage);
+        complete_deferral(&webhook, updating, response).await;
+    }
+    Some(deferral)
+}
 
-    let key = public_key.as_str();
+/// Sends a handler's late response after the interaction was deferred.
+///
+/// `updating` is set when the deferral was a deferred update, whose `@original` is the message
+/// holding the component. New messages are then sent as follow-ups, and only `UpdateMessage`
+/// edits the original. After a loading state, ephemeral messages replace it with an ephemeral
+/// follow-up, since the loading state itself is public.
+async fn complete_deferral(webhook: &InteractionWebhook, updating: bool, response: InteractionResponse) {
+    let result = match response {
+        InteractionResponse::UpdateMessage(data) => webhook.edit_original(&data).await.map(|_| ()),
+        InteractionResponse::ChannelMessageWithSource(data) if updating => {
+            webhook.create_followup(&data).await.map(|_| ())
+        }
+        InteractionResponse::ChannelMessageWithSource(data)
+            if data.flags.is_some_and(|flags| flags.contains(MessageFlags::EPHEMERAL)) =>
+        {
+            match webhook.delete_original().await {
+                Ok(()) => webhook.create_followup(&data).await.map(|_| ()),
+                Err(e) => Err(e),
+            }
+        }
+        InteractionResponse::ChannelMessageWithSource(data) => {
+            webhook.edit_original(&data).await.map(|_| ())
+        }
+        // The handler deferred itself and takes care of the original response.
+        InteractionResponse::DeferredChannelMessageWithSource(_) | InteractionResponse::DeferredUpdateMessage => {
+            Ok(())
+        }
+        other => {
+            tracing::warn!(
+                "Cannot send a response of type {} after deferring",
+                other.callback_type()
+            );
+            Ok(())
+        }
+    };
 
-    let body_str = from_utf8(body_bytes.as_ref()).map_err(|_| "Failed to convert body to string")?;
+    if let Err(e) = result {
+        tracing::error!("Failed to send the deferred response: {}", e);
+    }
+}
 
-    security::verify_discord_request(
-        key,
-        signature.to_str().unwrap(),
-        timestamp.to_str().unwrap(),
-        body_str,
-    )
+/// Handles webhook events, acknowledging each with the `204 No Content` Discord requires.
+///
+/// Events without a registered handler are acknowledged too, since Discord disables
+/// endpoints that keep failing.
+pub async fn event_handler(
+    Extension(verified): Extension<VerifiedApplications>,
+    body_bytes: Bytes,
+) -> Result<Response, Response> {
+    let event = serde_json::from_slice::<WebhookEvent>(&body_bytes).map_err(|e| {
+        tracing::warn!("Failed to parse webhook event: {}", e);
+        error_response("Invalid request", 400)
+    })?;
+
+    let application = verified.select(event.application_id()).ok_or_else(|| {
+        tracing::warn!(
+            "No verified application accepts events for {:?}",
+            event.application_id()
+        );
+        error_response("Unknown application", 404)
+    })?;
+
+    if let WebhookEvent::Event(webhook) = event {
+        if let EventData::Undecoded { event_type, .. } = &webhook.event.data {
+            tracing::warn!("Failed to decode the data of a {} event", event_type);
+        }
+        application.events.dispatch(webhook).await;
+    }
+
+    Ok(Response::builder().status(204).body(Body::empty()).unwrap())
+}
+
+/// Logs a failed verification and builds the 401 response for it.
+///
+/// Misconfiguration is logged as an error since it rejects every request, while
+/// routine rejections of bad or replayed requests are only warnings.
+fn rejection_response(error: &VerificationError) -> Response {
+    tracing::warn!("Rejected request: {}", error);
+    error_response("Invalid request", 401)
+}
+
+fn content_length(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// The raw bytes of a signature header, which must be valid UTF-8.
+fn header_bytes<'a>(headers: &'a HeaderMap, name: &'static str) -> Result<&'a [u8], VerificationError> {
+    let value = headers
+        .get(name)
+        .map(|value| value.as_bytes())
+        .ok_or(VerificationError::MissingHeader(name))?;
+    from_utf8(value).map_err(|_| VerificationError::NonUtf8Header(name))?;
+    Ok(value)
+}
+
+/// Verifies a request against each candidate application, returning those whose key signed it.
+fn validate_request(
+    headers: &HeaderMap,
+    body_bytes: &Bytes,
+    candidates: &[Arc<Application>],
+    replay_protection: Option<&ReplayProtection>,
+) -> Result<Vec<Arc<Application>>, VerificationError> {
+    let signature = header_bytes(headers, SIGNATURE_HEADER)?;
+    let timestamp = header_bytes(headers, TIMESTAMP_HEADER)?;
+
+    let mut verified = Vec::new();
+    for application in candidates {
+        match security::verify_with_any(&application.public_keys, signature, timestamp, body_bytes) {
+            Ok(key) => {
+                tracing::info!("Request verified with key {}", key.label);
+                verified.push(application.clone())
+            }
+            Err(VerificationError::InvalidSignature) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    if verified.is_empty() {
+        return Err(VerificationError::InvalidSignature);
+    }
+
+    if let Some(replay_protection) = replay_protection {
+        let timestamp = from_utf8(timestamp).map_err(|_| VerificationError::NonUtf8Header(TIMESTAMP_HEADER))?;
+        replay_protection.check_timestamp(timestamp)?;
+    }
+
+    Ok(verified)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VerifiedApplications;
+    use crate::model::primitives::DISCORD_EPOCH_MILLIS;
+    use crate::model::response::{InteractionCallbackData, InteractionResponse};
+    use crate::model::interactions::TestInteraction;
+    use crate::rest::tests::{message, mock_server};
+    use crate::rest::RestClient;
+    use crate::router::command::{CommandContext, CommandRouter};
+    use crate::router::component::{ComponentContext, ComponentRouter};
+    use tokio::sync::mpsc::UnboundedReceiver;
+    use axum::routing::patch;
+    use axum::Json;
+    use serde_json::{json, Value};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+    use crate::model::primitives::Snowflake;
+    use crate::security::{
+        generate_signature, parse_public_key, HexField, VerificationError, SIGNATURE_HEADER,
+        TIMESTAMP_HEADER,
+    };
+    use crate::{App, Application, DEFAULT_APPLICATION};
+    use ed25519_dalek::SigningKey;
+    use hex::ToHex;
+    use std::sync::Arc;
+    use axum::body::{Body, Bytes};
+    use axum::http::{HeaderMap, HeaderValue, Request, StatusCode};
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    const PUBLIC_KEY: &str = "f918b1bcd0ada9f101fb43d7c8412e0e9208f4f919a7f0a7e111f063513c3391";
+    const SIGNATURE: &str = "e926a0cba63cfa65588ae268dd055877276d6d4432b2dbb30882156b8fef4fd68706c46c303b9b23a9091a700d8e26e698fd9e979701f10fbf7d358a7adcba07";
+    const TIMESTAMP: &str = "1746297636";
+    const BODY: &str = r#"{"app_permissions":"562949953601536","application_id":"1096551423958855810","attachment_size_limit":524288000,"authorizing_integration_owners":{},"entitlements":[],"id":"0000000001","token":"example_token","type":1,"user":{"avatar":"c6a249645d46209f337279cd2ca998c7","avatar_decoration_data":null,"bot":true,"clan":null,"collectibles":null,"discriminator":"0000","global_name":"Discord","id":"643945264868098049","primary_guild":null,"public_flags":1,"system":true,"username":"discord"},"version":1}"#;
+
+    fn router() -> Router {
+        echo_router(Application::new(parse_public_key(PUBLIC_KEY).unwrap()))
+    }
+
+    fn echo_router(application: Application) -> Router {
+        limited_echo_router(application, crate::DEFAULT_MAX_BODY_SIZE)
+    }
+
+    fn limited_echo_router(application: Application, max_body_size: usize) -> Router {
+        let app = App::new()
+            .with_application(DEFAULT_APPLICATION, application)
+            .with_max_body_size(max_body_size);
+        Router::new()
+            .route("/echo", post(|body: Bytes| async move { body }))
+            .route_layer(axum::middleware::from_fn_with_state(
+                app.clone(),
+                super::verify_signature,
+            ))
+            .with_state(app)
+    }
+
+    fn request(signature: &str) -> Request<Body> {
+        Request::post("/echo")
+            .header("x-signature-ed25519", signature)
+            .header("x-signature-timestamp", TIMESTAMP)
+            .body(Body::from(BODY))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn pass_verified_body_downstream() {
+        let response = router().oneshot(request(SIGNATURE)).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, BODY.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn reject_unverified_requests() {
+        let tampered = SIGNATURE.replace("e926", "f926");
+        let response = router().oneshot(request(&tampered)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let unsigned = Request::post("/echo").body(Body::from(BODY)).unwrap();
+        let response = router().oneshot(unsigned).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn verify_raw_bytes_without_panicking() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let body: &[u8] = b"\xff\xfe not utf-8";
+        let signature = generate_signature(&signing_key, body, TIMESTAMP.as_bytes());
+        let router = echo_router(Application::new(signing_key.verifying_key()));
+
+        let signed = Request::post("/echo")
+            .header("x-signature-ed25519", signature.to_bytes().encode_hex::<String>())
+            .header("x-signature-timestamp", TIMESTAMP)
+            .body(Body::from(body))
+            .unwrap();
+        let response = router.clone().oneshot(signed).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let hostile = Request::post("/echo")
+            .header("x-signature-ed25519", HeaderValue::from_bytes(&[0xff; 128]).unwrap())
+            .header("x-signature-timestamp", HeaderValue::from_bytes(b"\xe9\x80").unwrap())
+            .body(Body::from(body))
+            .unwrap();
+        let response = router.oneshot(hostile).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[test]
+    fn report_non_utf8_headers() {
+        let applications = [Arc::new(other_application())];
+        let mut headers = HeaderMap::new();
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_bytes(&[0xff; 128]).unwrap());
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_static(TIMESTAMP));
+        let result = super::validate_request(&headers, &Bytes::from(BODY), &applications, None);
+        assert_eq!(result.err(), Some(VerificationError::NonUtf8Header(SIGNATURE_HEADER)));
+
+        headers.insert(SIGNATURE_HEADER, HeaderValue::from_static("abcd"));
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_bytes(b"\xe9\x80").unwrap());
+        let result = super::validate_request(&headers, &Bytes::from(BODY), &applications, None);
+        assert_eq!(result.err(), Some(VerificationError::NonUtf8Header(TIMESTAMP_HEADER)));
+
+        headers.insert(TIMESTAMP_HEADER, HeaderValue::from_static(TIMESTAMP));
+        let result = super::validate_request(&headers, &Bytes::from(BODY), &applications, None);
+        assert_eq!(result.err(), Some(VerificationError::MalformedHex(HexField::Signature)));
+    }
+
+    #[tokio::test]
+    async fn reject_oversized_bodies() {
+        let application = Application::new(parse_public_key(PUBLIC_KEY).unwrap());
+        let router = limited_echo_router(application, 64);
+
+        let declared = Request::post("/echo")
+            .header("content-length", "1048576")
+            .header("x-signature-ed25519", SIGNATURE)
+            .header("x-signature-timestamp", TIMESTAMP)
+            .body(Body::from("{}"))
+            .unwrap();
+        let response = router.clone().oneshot(declared).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let response = router.oneshot(request(SIGNATURE)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn acknowledge_webhook_events() {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let app = App::new().with_application(
+            DEFAULT_APPLICATION,
+            Application::new(signing_key.verifying_key()),
+        );
+        let router = Router::new()
+            .route("/events", post(super::event_handler))
+            .route_layer(axum::middleware::from_fn_with_state(
+                app.clone(),
+                super::verify_signature,
+            ))
+            .with_state(app);
+
+        let body = r#"{"version":1,"application_id":"1096551423958855810","type":0}"#;
+        let signature = generate_signature(&signing_key, body.as_bytes(), TIMESTAMP.as_bytes());
+        let signed = Request::post("/events")
+            .header("x-signature-ed25519", signature.to_bytes().encode_hex::<String>())
+            .header("x-signature-timestamp", TIMESTAMP)
+            .body(Body::from(body))
+            .unwrap();
+        let response = router.clone().oneshot(signed).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let body = r#"{"version":1,"application_id":"1096551423958855810","type":1,"event":{"type":"APPLICATION_AUTHORIZED","timestamp":"2024-10-18T14:42:53.064834","data":{"scopes":"unexpected"}}}"#;
+        let signature = generate_signature(&signing_key, body.as_bytes(), TIMESTAMP.as_bytes());
+        let signed = Request::post("/events")
+            .header("x-signature-ed25519", signature.to_bytes().encode_hex::<String>())
+            .header("x-signature-timestamp", TIMESTAMP)
+            .body(Body::from(body))
+            .unwrap();
+        let response = router.clone().oneshot(signed).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let unsigned = Request::post("/events").body(Body::from(body)).unwrap();
+        let response = router.oneshot(unsigned).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    fn interaction_id() -> u64 {
+        let created = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as u64;
+        (created - DISCORD_EPOCH_MILLIS) << 22
+    }
+
+    fn command_body(name: &str) -> String {
+        let data = json!({"id": "1371622100000000000", "name": name, "type": 1});
+        TestInteraction::new(2, data).id(interaction_id()).json()
+    }
+
+    fn component_body(custom_id: &str) -> String {
+        let data = json!({"custom_id": custom_id, "component_type": 2});
+        TestInteraction::new(3, data).id(interaction_id()).json()
+    }
+
+    async fn slow(_: CommandContext) -> InteractionResponse {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        InteractionResponse::message("Done")
+    }
+
+    async fn slow_ephemeral(_: CommandContext) -> InteractionResponse {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        InteractionResponse::ephemeral("Secret")
+    }
+
+    async fn fast(_: CommandContext) -> InteractionResponse {
+        InteractionResponse::message("Pong!")
+    }
+
+    async fn slow_reply(_: ComponentContext) -> InteractionResponse {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        InteractionResponse::message("Reply")
+    }
+
+    async fn slow_update(_: ComponentContext) -> InteractionResponse {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        InteractionResponse::UpdateMessage(InteractionCallbackData::new().content("Updated"))
+    }
+
+    /// Serves a mock interaction webhook, recording each call as e.g. `PATCH Done`.
+    async fn mock_webhook() -> (RestClient, UnboundedReceiver<String>) {
+        let (calls, received) = tokio::sync::mpsc::unbounded_channel();
+        let message = || Json(message("3", ""));
+        let (callbacks, edits, deletes, followups) = (calls.clone(), calls.clone(), calls.clone(), calls);
+        let mock = Router::new()
+            .route(
+                "/interactions/{id}/{token}/callback",
+                post(move |Json(body): Json<Value>| {
+                    callbacks.send(format!("CALLBACK {}", body["type"])).unwrap();
+                    async { StatusCode::NO_CONTENT }
+                }),
+            )
+            .route(
+                "/webhooks/{application}/{token}/messages/@original",
+                patch(move |Json(body): Json<Value>| {
+                    edits.send(format!("PATCH {}", body["content"].as_str().unwrap())).unwrap();
+                    async move { message() }
+                })
+                .delete(move || {
+                    deletes.send("DELETE".to_string()).unwrap();
+                    async { StatusCode::NO_CONTENT }
+                }),
+            )
+            .route(
+                "/webhooks/{application}/{token}",
+                post(move |Json(body): Json<Value>| {
+                    followups
+                        .send(format!("POST {} {}", body["content"].as_str().unwrap(), body["flags"]))
+                        .unwrap();
+                    async move { message() }
+                }),
+            );
+        let rest = RestClient::unauthenticated().with_base_url(&mock_server(mock).await);
+        (rest, received)
+    }
+
+    /// Serves `application`, signed with the key of [`other_application`], behind a 50ms watchdog.
+    fn watched_router(application: Application) -> Router {
+        let app = App::new()
+            .with_application(DEFAULT_APPLICATION, application)
+            .with_deferral_watchdog(Duration::from_millis(50));
+        Router::new()
+            .route("/", post(super::interaction_handler))
+            .route_layer(axum::middleware::from_fn_with_state(
+                app.clone(),
+                super::verify_signature,
+            ))
+            .with_state(app)
+    }
+
+    fn signed(body: String) -> Request<Body> {
+        let signing_key = SigningKey::from_bytes(&[7; 32]);
+        let signature = generate_signature(&signing_key, body.as_bytes(), TIMESTAMP.as_bytes());
+        Request::post("/")
+            .header("x-signature-ed25519", signature.to_bytes().encode_hex::<String>())
+            .header("x-signature-timestamp", TIMESTAMP)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn response_body(router: &Router, body: String) -> Bytes {
+        let response = router.clone().oneshot(signed(body)).await.unwrap();
+        axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap()
+    }
+
+    /// The webhook calls made before the response was returned, checking that none follow it.
+    async fn calls_before_response(received: &mut UnboundedReceiver<String>) -> Vec<String> {
+        let mut calls = Vec::new();
+        while let Ok(call) = received.try_recv() {
+            calls.push(call);
+        }
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(received.try_recv().is_err(), "Webhook called after the response was returned");
+        calls
+    }
+
+    #[tokio::test]
+    async fn defer_slow_handlers() {
+        let (rest, mut received) = mock_webhook().await;
+        let router = watched_router(
+            other_application()
+                .with_commands(CommandRouter::new().chat_input("slow", slow).chat_input("fast", fast))
+                .with_rest_client(rest),
+        );
+
+        let body = response_body(&router, command_body("slow")).await;
+        assert_eq!(body, r#"{"type":5}"#.as_bytes());
+        assert_eq!(calls_before_response(&mut received).await, ["CALLBACK 5", "PATCH Done"]);
+
+        let body = response_body(&router, command_body("fast")).await;
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["type"], 4);
+        assert_eq!(body["data"]["content"], "Pong!");
+        assert!(calls_before_response(&mut received).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn defer_slow_ephemeral_handlers_as_followups() {
+        let (rest, mut received) = mock_webhook().await;
+        let router = watched_router(
+            other_application()
+                .with_commands(CommandRouter::new().chat_input("secret", slow_ephemeral))
+                .with_rest_client(rest),
+        );
+
+        let body = response_body(&router, command_body("secret")).await;
+        assert_eq!(body, r#"{"type":5}"#.as_bytes());
+        assert_eq!(
+            calls_before_response(&mut received).await,
+            ["CALLBACK 5", "DELETE", "POST Secret 64"]
+        );
+    }
+
+    #[tokio::test]
+    async fn defer_slow_component_handlers() {
+        let (rest, mut received) = mock_webhook().await;
+        let router = watched_router(
+            other_application()
+                .with_components(
+                    ComponentRouter::new()
+                        .register("reply", slow_reply)
+                        .register("update", slow_update),
+                )
+                .with_rest_client(rest),
+        );
+
+        let body = response_body(&router, component_body("reply")).await;
+        assert_eq!(body, r#"{"type":6}"#.as_bytes());
+        assert_eq!(calls_before_response(&mut received).await, ["CALLBACK 6", "POST Reply null"]);
+
+        let body = response_body(&router, component_body("update")).await;
+        assert_eq!(body, r#"{"type":6}"#.as_bytes());
+        assert_eq!(calls_before_response(&mut received).await, ["CALLBACK 6", "PATCH Updated"]);
+    }
+
+    fn other_application() -> Application {
+        Application::new(SigningKey::from_bytes(&[7; 32]).verifying_key())
+    }
+
+    #[tokio::test]
+    async fn select_application_by_path() {
+        let app = App::new()
+            .with_application("other", other_application())
+            .with_application("pinger", Application::new(parse_public_key(PUBLIC_KEY).unwrap()));
+        let router = Router::new()
+            .route("/", post(super::interaction_handler))
+            .route("/apps/{app}", post(super::interaction_handler))
+            .route_layer(axum::middleware::from_fn_with_state(
+                app.clone(),
+                super::verify_signature,
+            ))
+            .with_state(app);
+
+        let signed = |uri: &str| {
+            Request::post(uri)
+                .header("x-signature-ed25519", SIGNATURE)
+                .header("x-signature-timestamp", TIMESTAMP)
+                .body(Body::from(BODY))
+                .unwrap()
+        };
+
+        let response = router.clone().oneshot(signed("/apps/pinger")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, r#"{"type":1}"#.as_bytes());
+
+        let response = router.clone().oneshot(signed("/")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = router.clone().oneshot(signed("/apps/other")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        let response = router.oneshot(signed("/apps/missing")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn select_application_by_id() {
+        let first = Snowflake::from(1);
+        let verified = VerifiedApplications(vec![
+            Arc::new(other_application().with_application_id(first.clone())),
+            Arc::new(other_application().with_bot_token("fallback")),
+        ]);
+
+        let selected = verified.select(&first).unwrap();
+        assert_eq!(selected.application_id(), Some(&first));
+
+        let selected = verified.select(&Snowflake::from(2)).unwrap();
+        assert_eq!(selected.bot_token(), Some("fallback"));
+
+        let verified = VerifiedApplications(vec![Arc::new(
+            other_application().with_application_id(first),
+        )]);
+        assert!(verified.select(&Snowflake::from(2)).is_none());
+    }
 }
@@ -1,62 +1,274 @@
+use crate::model::primitives::Snowflake;
 use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
 use hex::FromHex;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+pub const SIGNATURE_HEADER: &str = "x-signature-ed25519";
+pub const TIMESTAMP_HEADER: &str = "x-signature-timestamp";
+
+/// Why a request failed verification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VerificationError {
+    /// A required signature header was not sent.
+    MissingHeader(&'static str),
+    /// A signature header contains bytes that are not valid UTF-8.
+    NonUtf8Header(&'static str),
+    /// The public key or the signature is not valid hex of the expected length.
+    MalformedHex(HexField),
+    /// The configured public key is not a valid Ed25519 point.
+    InvalidPublicKey,
+    /// The signature does not match the timestamp and body.
+    InvalidSignature,
+    /// The timestamp header is not a number of seconds.
+    MalformedTimestamp,
+    /// The timestamp is outside the accepted freshness window.
+    StaleTimestamp,
+    /// The interaction ID was already seen within the freshness window.
+    ReplayedInteraction,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HexField {
+    PublicKey,
+    Signature,
+}
+
+impl Display for VerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VerificationError::MissingHeader(header) => write!(f, "Missing header {}", header),
+            VerificationError::NonUtf8Header(header) => write!(f, "Header {} is not valid UTF-8", header),
+            VerificationError::MalformedHex(HexField::PublicKey) => write!(f, "Failed to read public key"),
+            VerificationError::MalformedHex(HexField::Signature) => write!(f, "Failed to read signature"),
+            VerificationError::InvalidPublicKey => write!(f, "Invalid public key"),
+            VerificationError::InvalidSignature => write!(f, "Request body does not match signature"),
+            VerificationError::MalformedTimestamp => write!(f, "Failed to read timestamp"),
+            VerificationError::StaleTimestamp => write!(f, "Stale request timestamp"),
+            VerificationError::ReplayedInteraction => write!(f, "Replayed interaction"),
+        }
+    }
+}
+
+impl std::error::Error for VerificationError {}
+
+/// Parses an application's hex encoded public key, as shown in the Developer Portal.
+///
+/// # Errors
+///
+/// Returns an error if the key is not 64 hex characters or not a valid Ed25519 key.
+pub fn parse_public_key(public_key_hex: &str) -> Result<VerifyingKey, VerificationError> {
+    let public_key_bytes = <[u8; 32]>::from_hex(public_key_hex.trim())
+        .map_err(|_| VerificationError::MalformedHex(HexField::PublicKey))?;
+    VerifyingKey::from_bytes(&public_key_bytes).map_err(|_| VerificationError::InvalidPublicKey)
+}
 
 /// Verifies a Discord request by checking the provided signature against the message.
 ///
 /// # Arguments
 ///
-/// * `public_key_hex` - A string slice containing the public key in hexadecimal format.
-/// * `signature_hex` - A string slice containing the signature in hexadecimal format.
-/// * `timestamp` - A string slice representing the timestamp of the request.
-/// * `body` - A string slice containing the raw body of the request.
+/// * `verifying_key` - The application's public key, parsed with [`parse_public_key`].
+/// * `signature_hex` - The raw `x-signature-ed25519` header, the signature in hexadecimal format.
+/// * `timestamp` - The raw `x-signature-timestamp` header.
+/// * `body` - The raw body of the request, which need not be valid UTF-8.
 ///
 /// # Returns
 ///
 /// * `Ok(())` - If the signature is valid and matches the message.
-/// * `Err(String)` - If the verification fails, with an error message explaining the failure.
+/// * `Err(VerificationError)` - If the verification fails, describing the failure.
 ///
 /// # Errors
 ///
 /// This function returns an error if:
-/// - The public key or signature cannot be parsed from hexadecimal.
-/// - The public key is invalid.
+/// - The signature cannot be parsed from hexadecimal.
 /// - The signature does not match the message.
+///
+/// It never panics, whatever bytes the request contains.
 pub fn verify_discord_request(
-    public_key_hex: &str,
-    body_signature: &str,
-    timestamp: &str,
-    body: &str,
-) -> Result<(), String> {
-
-    // Convert public key and signature from hex
-    let public_key_bytes =
-        <[u8; 32]>::from_hex(public_key_hex).map_err(|_| String::from("Failed to read public key"))?;
-    let signature_bytes =
-        <[u8; 64]>::from_hex(body_signature).map_err(|_| String::from("Failed to read signature"))?;
+    verifying_key: &VerifyingKey,
+    signature_hex: &[u8],
+    timestamp: &[u8],
+    body: &[u8],
+) -> Result<(), VerificationError> {
+
+    // Convert signature from hex
+    let signature_bytes = <[u8; 64]>::from_hex(signature_hex)
+        .map_err(|_| VerificationError::MalformedHex(HexField::Signature))?;
 
     // Reconstruct types
-    let verifying_key = VerifyingKey::from_bytes(&public_key_bytes)
-        .map_err(|_| String::from("Invalid public key"))?;
     let signature = Signature::from_bytes(&signature_bytes);
 
     // Message is timestamp and raw body
-    let message = [timestamp.as_bytes(), body.as_bytes()].concat();
+    let message = [timestamp, body].concat();
 
     // Verify signature
     verifying_key
         .verify(&message, &signature)
-        .map_err(|_| String::from("Request body does not match signature"))
+        .map_err(|_| VerificationError::InvalidSignature)
+}
+
+/// A verifying key with a label identifying it in logs, e.g. `current` or `previous`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LabelledKey {
+    pub label: String,
+    pub key: VerifyingKey,
+}
+
+impl LabelledKey {
+    pub fn new(label: &str, key: VerifyingKey) -> Self {
+        LabelledKey {
+            label: label.to_string(),
+            key,
+        }
+    }
+}
+
+/// Verifies a request against each key in turn, returning the first key that matches.
+///
+/// Accepting several keys lets an application rotate its key without downtime.
+///
+/// # Errors
+///
+/// Returns [`VerificationError::InvalidSignature`] if no key matches, or the error for a
+/// malformed signature.
+pub fn verify_with_any<'a>(
+    keys: &'a [LabelledKey],
+    signature_hex: &[u8],
+    timestamp: &[u8],
+    body: &[u8],
+) -> Result<&'a LabelledKey, VerificationError> {
+    for key in keys {
+        match verify_discord_request(&key.key, signature_hex, timestamp, body) {
+            Ok(()) => return Ok(key),
+            Err(VerificationError::InvalidSignature) => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Err(VerificationError::InvalidSignature)
+}
+
+/// A source of the current time, injectable so freshness checks can be tested.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+impl<F> Clock for F
+where
+    F: Fn() -> SystemTime + Send + Sync,
+{
+    fn now(&self) -> SystemTime {
+        self()
+    }
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Rejects replayed requests by the age of their signed `x-signature-timestamp` and,
+/// optionally, by remembering the interaction IDs seen within that window.
+///
+/// Seen IDs are kept in memory, so deduplication only covers requests handled by the
+/// same instance.
+pub struct ReplayProtection {
+    max_age: Duration,
+    max_future: Duration,
+    clock: Arc<dyn Clock>,
+    seen: Option<Mutex<HashMap<Snowflake, SystemTime>>>,
+}
+
+impl ReplayProtection {
+    /// Accepts timestamps at most `max_age` in the past or in the future.
+    pub fn new(max_age: Duration) -> Self {
+        ReplayProtection {
+            max_age,
+            max_future: max_age,
+            clock: Arc::new(SystemClock),
+            seen: None,
+        }
+    }
+
+    /// Sets how far in the future a timestamp may be, to allow for clock skew.
+    pub fn with_max_future(mut self, max_future: Duration) -> Self {
+        self.max_future = max_future;
+        self
+    }
+
+    pub fn with_clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// Also rejects interaction IDs already seen within the freshness window.
+    pub fn with_interaction_dedupe(mut self) -> Self {
+        self.seen = Some(Mutex::new(HashMap::new()));
+        self
+    }
+
+    /// Checks that a request timestamp, in Unix seconds, is within the freshness window.
+    pub fn check_timestamp(&self, timestamp: &str) -> Result<(), VerificationError> {
+        let seconds: u64 = timestamp
+            .trim()
+            .parse()
+            .map_err(|_| VerificationError::MalformedTimestamp)?;
+        let timestamp = UNIX_EPOCH
+            .checked_add(Duration::from_secs(seconds))
+            .ok_or(VerificationError::MalformedTimestamp)?;
+        let now = self.clock.now();
+
+        let fresh = match now.duration_since(timestamp) {
+            Ok(age) => age <= self.max_age,
+            Err(e) => e.duration() <= self.max_future,
+        };
+
+        if fresh {
+            Ok(())
+        } else {
+            Err(VerificationError::StaleTimestamp)
+        }
+    }
+
+    /// Records an interaction ID, failing if it was already seen within the freshness window.
+    ///
+    /// Does nothing unless deduplication is enabled.
+    pub fn check_interaction_id(&self, id: &Snowflake) -> Result<(), VerificationError> {
+        let Some(seen) = &self.seen else {
+            return Ok(());
+        };
+
+        let now = self.clock.now();
+        let retention = self.max_age + self.max_future;
+        let mut seen = seen.lock().unwrap_or_else(|e| e.into_inner());
+        seen.retain(|_, seen_at| {
+            now.duration_since(*seen_at)
+                .map(|elapsed| elapsed <= retention)
+                .unwrap_or(true)
+        });
+
+        if seen.insert(id.clone(), now).is_some() {
+            Err(VerificationError::ReplayedInteraction)
+        } else {
+            Ok(())
+        }
+    }
 }
 
-#[allow(dead_code)]
-pub fn generate_signature(signing_key: &SigningKey, body: &str, timestamp: &str) -> Signature {
-    let message = [timestamp.as_bytes(), body.as_bytes()].concat();
+/// Signs a timestamp and body the way Discord does, for generating test requests.
+pub fn generate_signature(signing_key: &SigningKey, body: &[u8], timestamp: &[u8]) -> Signature {
+    let message = [timestamp, body].concat();
     signing_key.sign(message.as_slice())
 }
 
 #[cfg(test)]
 mod tests {
+    use super::{HexField, LabelledKey, ReplayProtection, VerificationError};
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
     use ed25519_dalek::SigningKey;
     use rand::rngs::OsRng;
     use crate::security::generate_signature;
@@ -68,11 +280,18 @@ mod tests {
         let timestamp = "1746297636";
         let signing_key = SigningKey::generate(&mut OsRng);
         
-        let signature = generate_signature(&signing_key, body, timestamp);
-        println!("Verifying Key: {}", signing_key.verifying_key().to_bytes().encode_hex::<String>());
-        println!("Signature: {}", signature.to_bytes().encode_hex::<String>());
+        let signature = generate_signature(&signing_key, body.as_bytes(), timestamp.as_bytes());
+        let signature_hex = signature.to_bytes().encode_hex::<String>();
+
+        let result = super::verify_discord_request(
+            &signing_key.verifying_key(),
+            signature_hex.as_bytes(),
+            timestamp.as_bytes(),
+            body.as_bytes(),
+        );
+        assert!(result.is_ok(), "Generated signature failed verification: {:?}", result);
     }
-    
+
     #[test]
     fn test_verify_discord_request() {
         let signature = "e926a0cba63cfa65588ae268dd055877276d6d4432b2dbb30882156b8fef4fd68706c46c303b9b23a9091a700d8e26e698fd9e979701f10fbf7d358a7adcba07";
@@ -80,9 +299,78 @@ mod tests {
 
         let body = r#"{"app_permissions":"562949953601536","application_id":"1096551423958855810","attachment_size_limit":524288000,"authorizing_integration_owners":{},"entitlements":[],"id":"0000000001","token":"example_token","type":1,"user":{"avatar":"c6a249645d46209f337279cd2ca998c7","avatar_decoration_data":null,"bot":true,"clan":null,"collectibles":null,"discriminator":"0000","global_name":"Discord","id":"643945264868098049","primary_guild":null,"public_flags":1,"system":true,"username":"discord"},"version":1}"#;
 
-        let public_key = "f918b1bcd0ada9f101fb43d7c8412e0e9208f4f919a7f0a7e111f063513c3391";
-        let result = super::verify_discord_request(public_key, signature, timestamp, body);
+        let public_key = super::parse_public_key("f918b1bcd0ada9f101fb43d7c8412e0e9208f4f919a7f0a7e111f063513c3391").unwrap();
+        let result = super::verify_discord_request(&public_key, signature.as_bytes(), timestamp.as_bytes(), body.as_bytes());
 
         assert!(result.is_ok(), "Signature verification failed: {:?}", result);
     }
+
+    fn fixed_clock(seconds: u64) -> impl Fn() -> SystemTime {
+        move || UNIX_EPOCH + Duration::from_secs(seconds)
+    }
+
+    #[test]
+    fn reject_stale_timestamps() {
+        let protection = ReplayProtection::new(Duration::from_secs(300))
+            .with_max_future(Duration::from_secs(5))
+            .with_clock(fixed_clock(1746297636));
+
+        assert!(protection.check_timestamp("1746297636").is_ok());
+        assert!(protection.check_timestamp("1746297336").is_ok());
+        assert!(protection.check_timestamp("1746297641").is_ok());
+        assert_eq!(protection.check_timestamp("1746297335"), Err(VerificationError::StaleTimestamp));
+        assert_eq!(protection.check_timestamp("1746297642"), Err(VerificationError::StaleTimestamp));
+        assert_eq!(protection.check_timestamp("yesterday"), Err(VerificationError::MalformedTimestamp));
+        assert_eq!(protection.check_timestamp(&u64::MAX.to_string()), Err(VerificationError::MalformedTimestamp));
+    }
+
+    #[test]
+    fn reject_replayed_interaction_ids() {
+        let protection = ReplayProtection::new(Duration::from_secs(300))
+            .with_clock(fixed_clock(1746297636))
+            .with_interaction_dedupe();
+        let id = serde_json::from_str(r#""1371622186951114924""#).unwrap();
+
+        assert!(protection.check_interaction_id(&id).is_ok());
+        assert_eq!(protection.check_interaction_id(&id), Err(VerificationError::ReplayedInteraction));
+    }
+
+    #[test]
+    fn report_malformed_keys_and_signatures() {
+        let signature = "e926a0cba63cfa65588ae268dd055877276d6d4432b2dbb30882156b8fef4fd68706c46c303b9b23a9091a700d8e26e698fd9e979701f10fbf7d358a7adcba07";
+        let public_key = super::parse_public_key("f918b1bcd0ada9f101fb43d7c8412e0e9208f4f919a7f0a7e111f063513c3391").unwrap();
+
+        let result = super::parse_public_key("");
+        assert_eq!(result, Err(VerificationError::MalformedHex(HexField::PublicKey)));
+
+        let result = super::verify_discord_request(&public_key, b"zz", b"1746297636", b"{}");
+        assert_eq!(result, Err(VerificationError::MalformedHex(HexField::Signature)));
+        assert_eq!(
+            VerificationError::NonUtf8Header(super::SIGNATURE_HEADER).to_string(),
+            "Header x-signature-ed25519 is not valid UTF-8"
+        );
+
+        let result = super::verify_discord_request(&public_key, signature.as_bytes(), b"1746297636", b"{}");
+        assert_eq!(result, Err(VerificationError::InvalidSignature));
+    }
+
+    #[test]
+    fn verify_with_any_labelled_key() {
+        let current = SigningKey::from_bytes(&[1; 32]);
+        let previous = SigningKey::from_bytes(&[2; 32]);
+        let keys = [
+            LabelledKey::new("current", current.verifying_key()),
+            LabelledKey::new("previous", previous.verifying_key()),
+        ];
+        let signed_by = |signing_key: &SigningKey| {
+            generate_signature(signing_key, b"{}", b"1746297636").to_bytes().encode_hex::<String>()
+        };
+
+        let matched = super::verify_with_any(&keys, signed_by(&previous).as_bytes(), b"1746297636", b"{}");
+        assert_eq!(matched.unwrap().label, "previous");
+
+        let retired = SigningKey::from_bytes(&[3; 32]);
+        let result = super::verify_with_any(&keys, signed_by(&retired).as_bytes(), b"1746297636", b"{}");
+        assert_eq!(result, Err(VerificationError::InvalidSignature));
+    }
 }